@@ -1,4 +1,6 @@
 #include <stddef.h>
 #include <aeronmd.h>
 #include <aeron_driver_context.h>
+#include <aeron_position.h>
+#include <aeron_system_counters.h>
 #include <command/aeron_control_protocol.h>
@@ -100,6 +100,7 @@ pub fn main() {
         .whitelist_type("aeron_.*")
         .whitelist_var("AERON_.*")
         .constified_enum_module("aeron_.*_enum")
+        .constified_enum_module("aeron_system_counter_enum_stct")
         // Some padding structures use arrays > 120 elements,
         // so we can't derive Debug implementations
         .derive_debug(false)
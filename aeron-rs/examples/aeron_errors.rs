@@ -0,0 +1,91 @@
+//! A version of the `ErrorStat` tool for printing the distinct errors recorded by a
+//! media driver, based on [ErrorStat.java](https://github.com/real-logic/aeron/blob/master/aeron-samples/src/main/java/io/aeron/samples/ErrorStat.java)
+mod common;
+
+use aeron_rs::cnc_descriptor;
+use aeron_rs::concurrent::errors::error_log_reader;
+use aeron_rs::context::ClientContext;
+use chrono::DateTime;
+use clap::{App, Arg};
+use common::format_millis;
+use serde_json::json;
+use std::path::PathBuf;
+
+fn parse_since(since: &str) -> i64 {
+    since
+        .parse::<i64>()
+        .or_else(|_| DateTime::parse_from_rfc3339(since).map(|t| t.timestamp_millis()))
+        .expect("--since must be milliseconds since the epoch or an RFC 3339 timestamp")
+}
+
+fn main() {
+    let matches = App::new("aeron-errors")
+        .about("Print the distinct errors recorded by a media driver")
+        .arg(
+            Arg::with_name("dir")
+                .long("dir")
+                .takes_value(true)
+                .help("Aeron directory containing the CnC file"),
+        )
+        .arg(
+            Arg::with_name("since")
+                .long("since")
+                .takes_value(true)
+                .help(
+                    "Only print errors last observed at or after this time, given as \
+                     milliseconds since the epoch or an RFC 3339 timestamp",
+                ),
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .help("Print errors as a JSON array"),
+        )
+        .get_matches();
+
+    let aeron_dir = matches
+        .value_of("dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(ClientContext::default_aeron_path);
+    let since = matches.value_of("since").map(parse_since).unwrap_or(0);
+    let as_json = matches.is_present("json");
+
+    let cnc = cnc_descriptor::map_cnc_file(&aeron_dir).expect("Unable to map CnC file");
+    let error_log = cnc_descriptor::error_log_buffer(&cnc).expect("Unable to find error log");
+
+    let mut errors = Vec::new();
+    let entries = error_log_reader::read(
+        &error_log,
+        |observation_count, first_timestamp, last_timestamp, error| {
+            if as_json {
+                errors.push(json!({
+                    "observation_count": observation_count,
+                    "first_observation": format_millis(first_timestamp),
+                    "first_observation_timestamp": first_timestamp,
+                    "last_observation": format_millis(last_timestamp),
+                    "last_observation_timestamp": last_timestamp,
+                    "error": error,
+                }));
+            } else {
+                println!(
+                    "***\n{} observations from {} to {} for:\n {}",
+                    observation_count,
+                    format_millis(first_timestamp),
+                    format_millis(last_timestamp),
+                    error
+                );
+            }
+        },
+        since,
+    )
+    .expect("Unable to read error log");
+
+    if as_json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&errors).expect("Unable to encode errors")
+        );
+    } else {
+        println!("\n{} distinct errors observed.", entries);
+    }
+}
@@ -0,0 +1,49 @@
+//! A version of the `LossStat` tool for printing the loss observed by a media driver,
+//! based on [LossStat.java](https://github.com/real-logic/aeron/blob/master/aeron-samples/src/main/java/io/aeron/samples/LossStat.java)
+mod common;
+
+use aeron_rs::context::ClientContext;
+use aeron_rs::loss_report;
+use clap::{App, Arg};
+use common::format_millis;
+use std::path::PathBuf;
+
+fn main() {
+    let matches = App::new("aeron-loss")
+        .about("Print the loss observed by a media driver, broken down by stream")
+        .arg(
+            Arg::with_name("dir")
+                .long("dir")
+                .takes_value(true)
+                .help("Aeron directory containing the loss report"),
+        )
+        .get_matches();
+
+    let aeron_dir = matches
+        .value_of("dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(ClientContext::default_aeron_path);
+
+    let report = loss_report::map_loss_report(&aeron_dir).expect("Unable to map loss report");
+
+    println!(
+        "#OBSERVATION_COUNT,TOTAL_BYTES_LOST,FIRST_OBSERVATION,LAST_OBSERVATION,\
+         SESSION_ID,STREAM_ID,CHANNEL,SOURCE"
+    );
+    let entries = loss_report::read(&report, |entry| {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            entry.observation_count,
+            entry.total_bytes_lost,
+            format_millis(entry.first_observation_timestamp),
+            format_millis(entry.last_observation_timestamp),
+            entry.session_id,
+            entry.stream_id,
+            entry.channel,
+            entry.source
+        );
+    })
+    .expect("Unable to read loss report");
+
+    println!("{} loss entries observed.", entries);
+}
@@ -0,0 +1,150 @@
+//! A version of the `AeronStat` tool for printing the counters of a running media driver,
+//! based on [AeronStat.java](https://github.com/real-logic/aeron/blob/master/aeron-samples/src/main/java/io/aeron/samples/AeronStat.java)
+mod common;
+
+use aeron_rs::cnc_descriptor;
+use aeron_rs::cnc_descriptor::{MetaDataDefinition, CNC_VERSION, META_DATA_LENGTH};
+use aeron_rs::concurrent::ringbuffer::ManyToOneRingBuffer;
+use aeron_rs::concurrent::AtomicBuffer;
+use aeron_rs::context::ClientContext;
+use aeron_rs::util::time::epoch_millis;
+use aeron_rs::util::{AeronError, IndexT};
+use clap::{App, Arg};
+use common::format_millis;
+use regex::Regex;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let matches = App::new("aeron-stat")
+        .about("Print the counters of a running media driver")
+        .arg(
+            Arg::with_name("dir")
+                .long("dir")
+                .takes_value(true)
+                .help("Aeron directory containing the CnC file"),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .takes_value(true)
+                .default_value("1000")
+                .help("Interval in milliseconds between updates"),
+        )
+        .arg(
+            Arg::with_name("once")
+                .long("once")
+                .help("Print the counters once and exit"),
+        )
+        .arg(
+            Arg::with_name("type-id")
+                .long("type-id")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Only print counters with this type identifier"),
+        )
+        .arg(
+            Arg::with_name("label")
+                .long("label")
+                .takes_value(true)
+                .help("Only print counters with labels matching this regular expression"),
+        )
+        .arg(
+            Arg::with_name("pid")
+                .long("pid")
+                .takes_value(true)
+                .help("Only print counters if the media driver has this process identifier"),
+        )
+        .get_matches();
+
+    let aeron_dir = matches
+        .value_of("dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(ClientContext::default_aeron_path);
+    let watch_interval = matches
+        .value_of("watch")
+        .and_then(|w| w.parse::<u64>().ok())
+        .expect("Watch interval must be a number of milliseconds");
+    let type_ids = matches
+        .values_of("type-id")
+        .map(|ids| {
+            ids.map(|id| id.parse::<i32>().expect("Type identifier must be a number"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let label = matches
+        .value_of("label")
+        .map(|l| Regex::new(l).expect("Invalid label regular expression"));
+    let pid = matches.value_of("pid").map(|p| {
+        p.parse::<i64>()
+            .expect("Process identifier must be a number")
+    });
+
+    let cnc = cnc_descriptor::map_cnc_file(&aeron_dir).expect("Unable to map CnC file");
+    let metadata = cnc
+        .overlay::<MetaDataDefinition>(0)
+        .expect("CnC file too small for metadata");
+    if metadata.cnc_version != CNC_VERSION {
+        eprintln!(
+            "{}",
+            AeronError::CncVersionMismatch {
+                expected: CNC_VERSION,
+                actual: metadata.cnc_version,
+            }
+        );
+        process::exit(1);
+    }
+    if let Some(pid) = pid {
+        if metadata.pid != pid {
+            eprintln!("Media driver PID is {}, not {}", metadata.pid, pid);
+            process::exit(1);
+        }
+    }
+
+    // SAFETY: The ring buffer only accesses the CnC file through `AtomicBuffer` operations
+    let to_driver = ManyToOneRingBuffer::new(
+        unsafe { cnc.view(META_DATA_LENGTH as IndexT, metadata.to_driver_buffer_length) }
+            .expect("CnC file too small for to-driver buffer"),
+    )
+    .expect("Invalid to-driver buffer");
+    let counters = cnc_descriptor::counters_reader(&cnc).expect("Unable to read counters");
+
+    loop {
+        let now = epoch_millis();
+        println!(
+            "{} - Aeron Stat (CnC v{}), pid {}, start {}, heartbeat age {}ms",
+            format_millis(now),
+            metadata.cnc_version,
+            metadata.pid,
+            format_millis(metadata.start_timestamp),
+            now - to_driver.consumer_heartbeat_time()
+        );
+        println!("{}", "=".repeat(80));
+
+        counters
+            .for_each(|counter_id, type_id, _key, counter_label| {
+                if !type_ids.is_empty() && !type_ids.contains(&type_id) {
+                    return;
+                }
+                if let Some(label) = label.as_ref() {
+                    if !label.is_match(counter_label) {
+                        return;
+                    }
+                }
+
+                if let Ok(value) = counters.counter_value(counter_id) {
+                    println!("{:3}: {:>20} - {}", counter_id, value, counter_label);
+                }
+            })
+            .expect("Unable to read counters");
+        println!("--");
+
+        if matches.is_present("once") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(watch_interval));
+    }
+}
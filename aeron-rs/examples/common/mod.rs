@@ -0,0 +1,11 @@
+//! Helpers shared by the tools reading a media driver's CnC file and reports
+use chrono::{TimeZone, Utc};
+
+/// Format milliseconds since the epoch as a UTC timestamp, falling back to the raw
+/// value if it's out of range
+pub fn format_millis(millis: i64) -> String {
+    match Utc.timestamp_millis_opt(millis).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+        None => format!("{}ms", millis),
+    }
+}
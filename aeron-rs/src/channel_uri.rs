@@ -0,0 +1,1054 @@
+//! Parse and validate the channel strings used to identify where a publication sends
+//! and a subscription receives. Channels look like:
+//!
+//! ```text
+//! aeron:udp?endpoint=localhost:40123|mtu=1408|reliable=true
+//! aeron:ipc?term-length=64k
+//! aeron-spy:aeron:udp?endpoint=224.0.1.1:40456|interface=192.168.0.1
+//! ```
+//!
+//! Parameters are kept in the order they were given, so a parsed channel converts
+//! back to the same string.
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Prefix shared by all Aeron channels
+pub const AERON_SCHEME: &str = "aeron";
+
+/// Qualifier used to spy on the outgoing traffic of a publication
+pub const SPY_QUALIFIER: &str = "aeron-spy";
+
+/// Media name for channels that communicate over UDP
+pub const UDP_MEDIA: &str = "udp";
+
+/// Media name for channels that communicate over shared memory
+pub const IPC_MEDIA: &str = "ipc";
+
+/// Prefix for parameter values that refer to a tag rather than a literal value
+pub const TAG_PREFIX: &str = "tag:";
+
+/// Address and port to send to (publications) or receive on (subscriptions)
+pub const ENDPOINT_PARAM_NAME: &str = "endpoint";
+
+/// Address and optional port of the local interface to use
+pub const INTERFACE_PARAM_NAME: &str = "interface";
+
+/// Address and port used for dynamic or manual control of multi-destination-cast
+pub const MDC_CONTROL_PARAM_NAME: &str = "control";
+
+/// Control mode for multi-destination-cast; either `manual` or `dynamic`
+pub const MDC_CONTROL_MODE_PARAM_NAME: &str = "control-mode";
+
+/// Manual control mode, where destinations are added and removed explicitly
+pub const MDC_CONTROL_MODE_MANUAL: &str = "manual";
+
+/// Dynamic control mode, where receivers join by sending status messages
+pub const MDC_CONTROL_MODE_DYNAMIC: &str = "dynamic";
+
+/// Maximum transmission unit for messages on the channel
+pub const MTU_LENGTH_PARAM_NAME: &str = "mtu";
+
+/// Length of each term in the publication log buffers
+pub const TERM_LENGTH_PARAM_NAME: &str = "term-length";
+
+/// Time-to-live for multicast datagrams
+pub const TTL_PARAM_NAME: &str = "ttl";
+
+/// Session identifier for the publication, or a tag referring to one
+pub const SESSION_ID_PARAM_NAME: &str = "session-id";
+
+/// Whether lost messages should be recovered (`true`) or gap-filled (`false`)
+pub const RELIABLE_STREAM_PARAM_NAME: &str = "reliable";
+
+/// Time (in nanoseconds, or with a `ns`/`us`/`ms`/`s` suffix) to linger a closed publication
+pub const LINGER_PARAM_NAME: &str = "linger";
+
+/// Initial term identifier for a publication's starting position
+pub const INITIAL_TERM_ID_PARAM_NAME: &str = "init-term-id";
+
+/// Term identifier for a publication's starting position
+pub const TERM_ID_PARAM_NAME: &str = "term-id";
+
+/// Offset within the term for a publication's starting position
+pub const TERM_OFFSET_PARAM_NAME: &str = "term-offset";
+
+/// Whether log buffers should be allocated as sparse files
+pub const SPARSE_PARAM_NAME: &str = "sparse";
+
+/// Whether end-of-stream should be signalled when a publication closes
+pub const EOS_PARAM_NAME: &str = "eos";
+
+/// Whether a subscription should apply flow control to the publication
+pub const TETHER_PARAM_NAME: &str = "tether";
+
+/// Whether a subscription should behave as part of a group (multicast semantics)
+pub const GROUP_PARAM_NAME: &str = "group";
+
+/// Whether a subscription should rejoin a stream after its image goes away
+pub const REJOIN_PARAM_NAME: &str = "rejoin";
+
+/// Comma-separated tags for the channel and the publication or subscription
+pub const TAGS_PARAM_NAME: &str = "tags";
+
+/// Human-readable alias for the channel, not interpreted by the media driver
+pub const ALIAS_PARAM_NAME: &str = "alias";
+
+/// Flow control strategy for the publication
+pub const FLOW_CONTROL_PARAM_NAME: &str = "fc";
+
+/// Alignment of frames within a term; the MTU must be a multiple of this
+pub const FRAME_ALIGNMENT: u32 = 32;
+
+/// Largest MTU that fits in a UDP datagram, rounded down to the frame alignment
+pub const MAX_UDP_PAYLOAD_LENGTH: u32 = 65504;
+
+/// Smallest allowed term length
+pub const TERM_MIN_LENGTH: u32 = 64 * 1024;
+
+/// Largest allowed term length
+pub const TERM_MAX_LENGTH: u32 = 1024 * 1024 * 1024;
+
+/// Transport media for a channel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Media {
+    /// Unicast or multicast UDP
+    Udp,
+    /// Shared memory on the same machine
+    Ipc,
+}
+
+impl Media {
+    /// Get the name of this media as it appears in a channel
+    pub fn name(self) -> &'static str {
+        match self {
+            Media::Udp => UDP_MEDIA,
+            Media::Ipc => IPC_MEDIA,
+        }
+    }
+}
+
+/// Reasons a channel string can fail to parse or validate. Positions are byte
+/// offsets into the channel string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChannelUriError {
+    /// Channel doesn't begin with `aeron:` (optionally preceded by `aeron-spy:`)
+    MissingScheme,
+    /// Media is neither `udp` nor `ipc`
+    UnknownMedia(String),
+    /// A parameter has no key
+    EmptyKey {
+        /// Position of the parameter in the channel string
+        position: usize,
+    },
+    /// A parameter key was not followed by `=`
+    MissingValue {
+        /// Parameter missing a value
+        key: String,
+        /// Position where the value was expected
+        position: usize,
+    },
+    /// A parameter appeared more than once
+    DuplicateParameter(String),
+    /// A known parameter has a value it can't accept
+    InvalidValue {
+        /// Parameter with the invalid value
+        key: String,
+        /// Value that was rejected
+        value: String,
+        /// Description of what the parameter accepts
+        reason: &'static str,
+    },
+    /// Builder was asked for a channel without choosing a media
+    MissingMedia,
+    /// Parameters were set that can't be used together
+    InvalidCombination(&'static str),
+}
+
+impl fmt::Display for ChannelUriError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChannelUriError::MissingScheme => {
+                write!(f, "channel must begin with '{}:'", AERON_SCHEME)
+            }
+            ChannelUriError::UnknownMedia(media) => write!(
+                f,
+                "unknown media '{}', expected '{}' or '{}'",
+                media, UDP_MEDIA, IPC_MEDIA
+            ),
+            ChannelUriError::EmptyKey { position } => {
+                write!(f, "empty parameter key at position {}", position)
+            }
+            ChannelUriError::MissingValue { key, position } => write!(
+                f,
+                "parameter '{}' has no value, expected '=' at position {}",
+                key, position
+            ),
+            ChannelUriError::DuplicateParameter(key) => {
+                write!(f, "parameter '{}' given more than once", key)
+            }
+            ChannelUriError::InvalidValue { key, value, reason } => write!(
+                f,
+                "invalid value '{}' for parameter '{}': {}",
+                value, key, reason
+            ),
+            ChannelUriError::MissingMedia => write!(f, "channel media must be set"),
+            ChannelUriError::InvalidCombination(reason) => {
+                write!(f, "invalid combination of parameters: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for ChannelUriError {}
+
+/// Parsed representation of a channel string
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelUri {
+    spy: bool,
+    media: Media,
+    params: Vec<(String, String)>,
+}
+
+impl ChannelUri {
+    /// Create a channel for a media with no parameters
+    pub fn new(media: Media) -> Self {
+        ChannelUri {
+            spy: false,
+            media,
+            params: Vec::new(),
+        }
+    }
+
+    /// Parse and validate a channel string
+    pub fn parse(channel: &str) -> Result<ChannelUri, ChannelUriError> {
+        let spy_prefix = format!("{}:", SPY_QUALIFIER);
+        let scheme_prefix = format!("{}:", AERON_SCHEME);
+
+        let (spy, mut position) = if channel.starts_with(&spy_prefix) {
+            (true, spy_prefix.len())
+        } else {
+            (false, 0)
+        };
+        if !channel[position..].starts_with(&scheme_prefix) {
+            return Err(ChannelUriError::MissingScheme);
+        }
+        position += scheme_prefix.len();
+
+        let rest = &channel[position..];
+        let (media, params) = match rest.find('?') {
+            Some(index) => (
+                &rest[..index],
+                Some((position + index + 1, &rest[index + 1..])),
+            ),
+            None => (rest, None),
+        };
+        let media = match media {
+            UDP_MEDIA => Media::Udp,
+            IPC_MEDIA => Media::Ipc,
+            _ => return Err(ChannelUriError::UnknownMedia(media.to_string())),
+        };
+
+        let mut uri = ChannelUri::new(media);
+        uri.spy = spy;
+
+        if let Some((mut position, params)) = params {
+            for param in params.split('|') {
+                let equals = match param.find('=') {
+                    Some(equals) => equals,
+                    None if param.is_empty() => return Err(ChannelUriError::EmptyKey { position }),
+                    None => {
+                        return Err(ChannelUriError::MissingValue {
+                            key: param.to_string(),
+                            position: position + param.len(),
+                        })
+                    }
+                };
+                if equals == 0 {
+                    return Err(ChannelUriError::EmptyKey { position });
+                }
+
+                let key = &param[..equals];
+                if uri.contains_key(key) {
+                    return Err(ChannelUriError::DuplicateParameter(key.to_string()));
+                }
+                uri.put(key, &param[equals + 1..])?;
+                position += param.len() + 1;
+            }
+        }
+
+        Ok(uri)
+    }
+
+    /// Get the transport media of this channel
+    pub fn media(&self) -> Media {
+        self.media
+    }
+
+    /// Check whether this channel spies on a publication's outgoing traffic
+    pub fn is_spy(&self) -> bool {
+        self.spy
+    }
+
+    /// Set whether this channel spies on a publication's outgoing traffic
+    pub fn set_spy(&mut self, spy: bool) {
+        self.spy = spy;
+    }
+
+    /// Get the value of a parameter
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Check whether a parameter has been set
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Set the value of a parameter, replacing any existing value. Known parameters
+    /// are validated before being set.
+    pub fn put(&mut self, key: &str, value: &str) -> Result<(), ChannelUriError> {
+        validate(key, value)?;
+        match self.params.iter_mut().find(|(k, _)| k == key) {
+            Some(param) => param.1 = value.to_string(),
+            None => self.params.push((key.to_string(), value.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Remove a parameter, returning its value if it was set
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.params.iter().position(|(k, _)| k == key)?;
+        Some(self.params.remove(index).1)
+    }
+
+    /// Iterate over all parameters in the order they were set
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Get the tag identifying this channel, if one was given. This is the first
+    /// element of the `tags` parameter.
+    pub fn channel_tag(&self) -> Option<&str> {
+        self.tag(0)
+    }
+
+    /// Get the tag identifying the publication or subscription, if one was given.
+    /// This is the second element of the `tags` parameter.
+    pub fn entity_tag(&self) -> Option<&str> {
+        self.tag(1)
+    }
+
+    fn tag(&self, index: usize) -> Option<&str> {
+        self.get(TAGS_PARAM_NAME)?
+            .split(',')
+            .nth(index)
+            .filter(|t| !t.is_empty())
+    }
+}
+
+impl FromStr for ChannelUri {
+    type Err = ChannelUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ChannelUri::parse(s)
+    }
+}
+
+impl fmt::Display for ChannelUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.spy {
+            write!(f, "{}:", SPY_QUALIFIER)?;
+        }
+        write!(f, "{}:{}", AERON_SCHEME, self.media.name())?;
+
+        for (index, (key, value)) in self.params.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '|' };
+            write!(f, "{}{}={}", separator, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Control mode for multi-destination-cast publications
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ControlMode {
+    /// Destinations are added and removed explicitly by the publisher
+    Manual,
+    /// Receivers join by sending status messages to the control endpoint
+    Dynamic,
+}
+
+impl ControlMode {
+    /// Get the name of this control mode as it appears in a channel
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlMode::Manual => MDC_CONTROL_MODE_MANUAL,
+            ControlMode::Dynamic => MDC_CONTROL_MODE_DYNAMIC,
+        }
+    }
+}
+
+/// Build channel strings from typed parameters. Values are validated the same way
+/// as when parsing, and parameters that depend on each other are checked when the
+/// channel is built.
+///
+/// ```
+/// # use aeron_rs::channel_uri::{ChannelUriStringBuilder, Media};
+/// let channel = ChannelUriStringBuilder::default()
+///     .set_media(Media::Udp)
+///     .set_endpoint("localhost:40123")
+///     .set_mtu(1408)
+///     .build()
+///     .unwrap();
+/// assert_eq!(channel, "aeron:udp?endpoint=localhost:40123|mtu=1408");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChannelUriStringBuilder {
+    spy: bool,
+    media: Option<Media>,
+    endpoint: Option<String>,
+    control_endpoint: Option<String>,
+    control_mode: Option<ControlMode>,
+    interface: Option<String>,
+    mtu: Option<u32>,
+    term_length: Option<u32>,
+    initial_term_id: Option<i32>,
+    term_id: Option<i32>,
+    term_offset: Option<u32>,
+    session_id: Option<i32>,
+    linger: Option<Duration>,
+    reliable: Option<bool>,
+    ttl: Option<u8>,
+    sparse: Option<bool>,
+    eos: Option<bool>,
+    tether: Option<bool>,
+    group: Option<bool>,
+    rejoin: Option<bool>,
+    flow_control: Option<String>,
+}
+
+impl ChannelUriStringBuilder {
+    /// Spy on the outgoing traffic of a publication rather than subscribing to it
+    pub fn set_spy(mut self, spy: bool) -> Self {
+        self.spy = spy;
+        self
+    }
+
+    /// Set the transport media; required before building
+    pub fn set_media(mut self, media: Media) -> Self {
+        self.media = Some(media);
+        self
+    }
+
+    /// Set the address and port to send to or receive on
+    pub fn set_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Set the address and port used for multi-destination-cast control
+    pub fn set_control_endpoint(mut self, control_endpoint: &str) -> Self {
+        self.control_endpoint = Some(control_endpoint.to_string());
+        self
+    }
+
+    /// Set how multi-destination-cast destinations are managed
+    pub fn set_control_mode(mut self, control_mode: ControlMode) -> Self {
+        self.control_mode = Some(control_mode);
+        self
+    }
+
+    /// Set the local interface to send or receive on
+    pub fn set_interface(mut self, interface: &str) -> Self {
+        self.interface = Some(interface.to_string());
+        self
+    }
+
+    /// Set the maximum transmission unit
+    pub fn set_mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    /// Set the length of each term in the log buffers
+    pub fn set_term_length(mut self, term_length: u32) -> Self {
+        self.term_length = Some(term_length);
+        self
+    }
+
+    /// Set the initial term identifier of the starting position. Must be set along
+    /// with the term identifier and term offset.
+    pub fn set_initial_term_id(mut self, initial_term_id: i32) -> Self {
+        self.initial_term_id = Some(initial_term_id);
+        self
+    }
+
+    /// Set the term identifier of the starting position. Must be set along with the
+    /// initial term identifier and term offset.
+    pub fn set_term_id(mut self, term_id: i32) -> Self {
+        self.term_id = Some(term_id);
+        self
+    }
+
+    /// Set the offset within the term of the starting position. Must be set along
+    /// with the initial term identifier and term identifier.
+    pub fn set_term_offset(mut self, term_offset: u32) -> Self {
+        self.term_offset = Some(term_offset);
+        self
+    }
+
+    /// Set the initial term identifier, term identifier and term offset together
+    pub fn set_initial_position(
+        self,
+        initial_term_id: i32,
+        term_id: i32,
+        term_offset: u32,
+    ) -> Self {
+        self.set_initial_term_id(initial_term_id)
+            .set_term_id(term_id)
+            .set_term_offset(term_offset)
+    }
+
+    /// Set the session identifier
+    pub fn set_session_id(mut self, session_id: i32) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Set how long a closed publication lingers before its resources are released
+    pub fn set_linger(mut self, linger: Duration) -> Self {
+        self.linger = Some(linger);
+        self
+    }
+
+    /// Set whether lost messages are recovered (`true`) or gap-filled (`false`)
+    pub fn set_reliable(mut self, reliable: bool) -> Self {
+        self.reliable = Some(reliable);
+        self
+    }
+
+    /// Set the time-to-live for multicast datagrams
+    pub fn set_ttl(mut self, ttl: u8) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set whether log buffers are allocated as sparse files
+    pub fn set_sparse(mut self, sparse: bool) -> Self {
+        self.sparse = Some(sparse);
+        self
+    }
+
+    /// Set whether end-of-stream is signalled when a publication closes
+    pub fn set_eos(mut self, eos: bool) -> Self {
+        self.eos = Some(eos);
+        self
+    }
+
+    /// Set whether a subscription applies flow control to the publication
+    pub fn set_tether(mut self, tether: bool) -> Self {
+        self.tether = Some(tether);
+        self
+    }
+
+    /// Set whether a subscription behaves as part of a group
+    pub fn set_group(mut self, group: bool) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Set whether a subscription rejoins a stream after its image goes away
+    pub fn set_rejoin(mut self, rejoin: bool) -> Self {
+        self.rejoin = Some(rejoin);
+        self
+    }
+
+    /// Set the flow control strategy, e.g. `min`, `max` or `tagged,g:123`
+    pub fn set_flow_control(mut self, flow_control: &str) -> Self {
+        self.flow_control = Some(flow_control.to_string());
+        self
+    }
+
+    /// Validate the parameters and build the channel
+    pub fn build_uri(&self) -> Result<ChannelUri, ChannelUriError> {
+        let media = self.media.ok_or(ChannelUriError::MissingMedia)?;
+
+        let position_count = [
+            self.initial_term_id.is_some(),
+            self.term_id.is_some(),
+            self.term_offset.is_some(),
+        ]
+        .iter()
+        .filter(|is_set| **is_set)
+        .count();
+        if position_count != 0 && position_count != 3 {
+            return Err(ChannelUriError::InvalidCombination(
+                "initial term id, term id and term offset must be set together",
+            ));
+        }
+        if let (Some(term_offset), Some(term_length)) = (self.term_offset, self.term_length) {
+            if term_offset >= term_length {
+                return Err(ChannelUriError::InvalidCombination(
+                    "term offset must be less than the term length",
+                ));
+            }
+        }
+        if media == Media::Ipc
+            && (self.endpoint.is_some()
+                || self.control_endpoint.is_some()
+                || self.control_mode.is_some()
+                || self.interface.is_some()
+                || self.ttl.is_some())
+        {
+            return Err(ChannelUriError::InvalidCombination(
+                "endpoint, control, control mode, interface and ttl only apply to udp",
+            ));
+        }
+        if self.control_mode == Some(ControlMode::Dynamic) && self.control_endpoint.is_none() {
+            return Err(ChannelUriError::InvalidCombination(
+                "dynamic control mode requires a control endpoint",
+            ));
+        }
+
+        let mut uri = ChannelUri::new(media);
+        uri.set_spy(self.spy);
+
+        let mut put = |key: &str, value: Option<String>| match value {
+            Some(value) => uri.put(key, &value),
+            None => Ok(()),
+        };
+        put(ENDPOINT_PARAM_NAME, self.endpoint.clone())?;
+        put(MDC_CONTROL_PARAM_NAME, self.control_endpoint.clone())?;
+        put(
+            MDC_CONTROL_MODE_PARAM_NAME,
+            self.control_mode.map(|m| m.name().to_string()),
+        )?;
+        put(INTERFACE_PARAM_NAME, self.interface.clone())?;
+        put(MTU_LENGTH_PARAM_NAME, self.mtu.map(|v| v.to_string()))?;
+        put(
+            TERM_LENGTH_PARAM_NAME,
+            self.term_length.map(|v| v.to_string()),
+        )?;
+        put(
+            INITIAL_TERM_ID_PARAM_NAME,
+            self.initial_term_id.map(|v| v.to_string()),
+        )?;
+        put(TERM_ID_PARAM_NAME, self.term_id.map(|v| v.to_string()))?;
+        put(
+            TERM_OFFSET_PARAM_NAME,
+            self.term_offset.map(|v| v.to_string()),
+        )?;
+        put(
+            SESSION_ID_PARAM_NAME,
+            self.session_id.map(|v| v.to_string()),
+        )?;
+        put(
+            LINGER_PARAM_NAME,
+            self.linger.map(|v| v.as_nanos().to_string()),
+        )?;
+        put(
+            RELIABLE_STREAM_PARAM_NAME,
+            self.reliable.map(|v| v.to_string()),
+        )?;
+        put(TTL_PARAM_NAME, self.ttl.map(|v| v.to_string()))?;
+        put(SPARSE_PARAM_NAME, self.sparse.map(|v| v.to_string()))?;
+        put(EOS_PARAM_NAME, self.eos.map(|v| v.to_string()))?;
+        put(TETHER_PARAM_NAME, self.tether.map(|v| v.to_string()))?;
+        put(GROUP_PARAM_NAME, self.group.map(|v| v.to_string()))?;
+        put(REJOIN_PARAM_NAME, self.rejoin.map(|v| v.to_string()))?;
+        put(FLOW_CONTROL_PARAM_NAME, self.flow_control.clone())?;
+
+        Ok(uri)
+    }
+
+    /// Validate the parameters and build the channel string
+    pub fn build(&self) -> Result<String, ChannelUriError> {
+        self.build_uri().map(|uri| uri.to_string())
+    }
+}
+
+fn invalid(key: &str, value: &str, reason: &'static str) -> ChannelUriError {
+    ChannelUriError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+        reason,
+    }
+}
+
+/// Parse a size that may use a `k`, `m` or `g` suffix, as the media driver does
+pub(crate) fn parse_size(value: &str) -> Option<u64> {
+    let (digits, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1024),
+        'm' | 'M' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' | 'G' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Parse a duration in nanoseconds that may use a `ns`, `us`, `ms` or `s` suffix,
+/// as the media driver does
+pub(crate) fn parse_duration_ns(value: &str) -> Option<u64> {
+    let suffixes: [(&str, u64); 4] = [
+        ("ns", 1),
+        ("us", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+    ];
+    let (digits, multiplier) = suffixes
+        .iter()
+        .find(|(suffix, _)| value.ends_with(suffix))
+        .map(|(suffix, multiplier)| (&value[..value.len() - suffix.len()], *multiplier))
+        .unwrap_or((value, 1));
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+fn validate_endpoint(key: &str, value: &str) -> Result<(), ChannelUriError> {
+    // IPv6 addresses are bracketed, so the port always follows the last colon
+    let port = match value.rfind(':') {
+        Some(index) if index > 0 => &value[index + 1..],
+        _ => return Err(invalid(key, value, "expected host:port")),
+    };
+    match port.parse::<u16>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(invalid(key, value, "port must be between 0 and 65535")),
+    }
+}
+
+fn validate_bool(key: &str, value: &str) -> Result<(), ChannelUriError> {
+    match value {
+        "true" | "false" => Ok(()),
+        _ => Err(invalid(key, value, "expected 'true' or 'false'")),
+    }
+}
+
+fn validate_i32(key: &str, value: &str) -> Result<(), ChannelUriError> {
+    value
+        .parse::<i32>()
+        .map(|_| ())
+        .map_err(|_| invalid(key, value, "expected a 32-bit integer"))
+}
+
+fn validate(key: &str, value: &str) -> Result<(), ChannelUriError> {
+    match key {
+        ENDPOINT_PARAM_NAME | MDC_CONTROL_PARAM_NAME => validate_endpoint(key, value),
+        INTERFACE_PARAM_NAME | ALIAS_PARAM_NAME | FLOW_CONTROL_PARAM_NAME => Ok(()),
+        MDC_CONTROL_MODE_PARAM_NAME => match value {
+            MDC_CONTROL_MODE_MANUAL | MDC_CONTROL_MODE_DYNAMIC => Ok(()),
+            _ => Err(invalid(key, value, "expected 'manual' or 'dynamic'")),
+        },
+        MTU_LENGTH_PARAM_NAME => match parse_size(value) {
+            Some(mtu)
+                if mtu >= u64::from(FRAME_ALIGNMENT)
+                    && mtu <= u64::from(MAX_UDP_PAYLOAD_LENGTH)
+                    && mtu % u64::from(FRAME_ALIGNMENT) == 0 =>
+            {
+                Ok(())
+            }
+            _ => Err(invalid(
+                key,
+                value,
+                "must be a multiple of 32 between 32 and 65504",
+            )),
+        },
+        TERM_LENGTH_PARAM_NAME => match parse_size(value) {
+            Some(length)
+                if length >= u64::from(TERM_MIN_LENGTH)
+                    && length <= u64::from(TERM_MAX_LENGTH)
+                    && length.is_power_of_two() =>
+            {
+                Ok(())
+            }
+            _ => Err(invalid(
+                key,
+                value,
+                "must be a power of two between 64k and 1g",
+            )),
+        },
+        TTL_PARAM_NAME => value
+            .parse::<u8>()
+            .map(|_| ())
+            .map_err(|_| invalid(key, value, "must be between 0 and 255")),
+        SESSION_ID_PARAM_NAME => match value.find(TAG_PREFIX) {
+            Some(0) => value
+                .trim_start_matches(TAG_PREFIX)
+                .parse::<i64>()
+                .map(|_| ())
+                .map_err(|_| invalid(key, value, "tag must be a 64-bit integer")),
+            _ => validate_i32(key, value),
+        },
+        RELIABLE_STREAM_PARAM_NAME
+        | SPARSE_PARAM_NAME
+        | EOS_PARAM_NAME
+        | TETHER_PARAM_NAME
+        | GROUP_PARAM_NAME
+        | REJOIN_PARAM_NAME => validate_bool(key, value),
+        LINGER_PARAM_NAME => parse_duration_ns(value).map(|_| ()).ok_or_else(|| {
+            invalid(
+                key,
+                value,
+                "expected nanoseconds, optionally with a ns, us, ms or s suffix",
+            )
+        }),
+        INITIAL_TERM_ID_PARAM_NAME | TERM_ID_PARAM_NAME => validate_i32(key, value),
+        TERM_OFFSET_PARAM_NAME => match value.parse::<u32>() {
+            Ok(offset) if offset % FRAME_ALIGNMENT == 0 && offset < TERM_MAX_LENGTH => Ok(()),
+            _ => Err(invalid(
+                key,
+                value,
+                "must be a non-negative multiple of 32 within the term",
+            )),
+        },
+        TAGS_PARAM_NAME => {
+            let tags: Vec<&str> = value.split(',').collect();
+            if tags.len() > 2
+                || tags
+                    .iter()
+                    .any(|t| !t.is_empty() && t.parse::<i64>().is_err())
+            {
+                Err(invalid(
+                    key,
+                    value,
+                    "expected a channel tag and optional entity tag, separated by a comma",
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        // Parameters we don't know about are passed through to the media driver
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel_uri::{
+        ChannelUri, ChannelUriError, ChannelUriStringBuilder, ControlMode, Media,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn should_parse_udp_channel() {
+        let uri = ChannelUri::parse("aeron:udp?endpoint=localhost:40123|mtu=1408").unwrap();
+        assert_eq!(uri.media(), Media::Udp);
+        assert!(!uri.is_spy());
+        assert_eq!(uri.get("endpoint"), Some("localhost:40123"));
+        assert_eq!(uri.get("mtu"), Some("1408"));
+        assert_eq!(uri.get("ttl"), None);
+    }
+
+    #[test]
+    fn should_parse_ipc_without_params() {
+        let uri = ChannelUri::parse("aeron:ipc").unwrap();
+        assert_eq!(uri.media(), Media::Ipc);
+        assert_eq!(uri.params().count(), 0);
+    }
+
+    #[test]
+    fn should_parse_spy_prefix_and_tags() {
+        let uri =
+            ChannelUri::parse("aeron-spy:aeron:udp?endpoint=224.0.1.1:40456|tags=1,2").unwrap();
+        assert!(uri.is_spy());
+        assert_eq!(uri.channel_tag(), Some("1"));
+        assert_eq!(uri.entity_tag(), Some("2"));
+
+        let uri = ChannelUri::parse("aeron:ipc?tags=3").unwrap();
+        assert_eq!(uri.channel_tag(), Some("3"));
+        assert_eq!(uri.entity_tag(), None);
+    }
+
+    #[test]
+    fn should_round_trip_to_string() {
+        for channel in &[
+            "aeron:ipc",
+            "aeron:ipc?term-length=64k|sparse=true",
+            "aeron:udp?endpoint=[fe80::1]:40123|interface=192.168.0.1/24|ttl=8",
+            "aeron-spy:aeron:udp?control=localhost:40124|control-mode=dynamic|session-id=tag:7",
+            "aeron:udp?endpoint=localhost:40123|linger=5ms|custom=value=with=equals",
+        ] {
+            assert_eq!(&ChannelUri::parse(channel).unwrap().to_string(), channel);
+        }
+    }
+
+    #[test]
+    fn should_reject_missing_scheme_and_unknown_media() {
+        assert_eq!(
+            ChannelUri::parse("udp?endpoint=localhost:40123"),
+            Err(ChannelUriError::MissingScheme)
+        );
+        assert_eq!(
+            ChannelUri::parse("aeron-spy:udp"),
+            Err(ChannelUriError::MissingScheme)
+        );
+        assert_eq!(
+            ChannelUri::parse("aeron:tcp?endpoint=localhost:40123"),
+            Err(ChannelUriError::UnknownMedia("tcp".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_report_position_of_malformed_params() {
+        assert_eq!(
+            ChannelUri::parse("aeron:udp?endpoint=localhost:40123|=1"),
+            Err(ChannelUriError::EmptyKey { position: 35 })
+        );
+        assert_eq!(
+            ChannelUri::parse("aeron:udp?endpoint=localhost:40123||mtu=1408"),
+            Err(ChannelUriError::EmptyKey { position: 35 })
+        );
+        assert_eq!(
+            ChannelUri::parse("aeron:udp?reliable"),
+            Err(ChannelUriError::MissingValue {
+                key: "reliable".to_string(),
+                position: 18
+            })
+        );
+        assert_eq!(
+            ChannelUri::parse("aeron:udp?mtu=1408|mtu=4096"),
+            Err(ChannelUriError::DuplicateParameter("mtu".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_validate_known_params() {
+        let invalid = [
+            "aeron:udp?endpoint=localhost",
+            "aeron:udp?endpoint=localhost:99999",
+            "aeron:udp?mtu=1000",
+            "aeron:udp?mtu=65536",
+            "aeron:ipc?term-length=100000",
+            "aeron:ipc?term-length=32k",
+            "aeron:udp?ttl=256",
+            "aeron:udp?session-id=abc",
+            "aeron:udp?reliable=yes",
+            "aeron:udp?linger=5 minutes",
+            "aeron:udp?control-mode=auto",
+            "aeron:udp?term-offset=33",
+            "aeron:udp?tags=1,2,3",
+        ];
+        for channel in invalid.iter() {
+            match ChannelUri::parse(channel) {
+                Err(ChannelUriError::InvalidValue { .. }) => (),
+                other => panic!("Expected {} to be invalid, got {:?}", channel, other),
+            }
+        }
+
+        let valid = [
+            "aeron:udp?mtu=8k",
+            "aeron:ipc?term-length=1g",
+            "aeron:udp?session-id=-5",
+            "aeron:udp?linger=0",
+            "aeron:udp?init-term-id=-100|term-id=-99|term-offset=64",
+        ];
+        for channel in valid.iter() {
+            assert!(ChannelUri::parse(channel).is_ok(), "{}", channel);
+        }
+    }
+
+    #[test]
+    fn should_describe_errors() {
+        let error = ChannelUri::parse("aeron:udp?ttl=300").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value '300' for parameter 'ttl': must be between 0 and 255"
+        );
+    }
+
+    #[test]
+    fn should_put_and_remove_params() {
+        let mut uri: ChannelUri = "aeron:udp?endpoint=localhost:40123".parse().unwrap();
+        uri.put("mtu", "4096").unwrap();
+        uri.put("endpoint", "localhost:40124").unwrap();
+        assert!(uri.put("mtu", "12").is_err());
+        assert_eq!(
+            uri.to_string(),
+            "aeron:udp?endpoint=localhost:40124|mtu=4096"
+        );
+
+        assert_eq!(uri.remove("endpoint"), Some("localhost:40124".to_string()));
+        assert_eq!(uri.remove("endpoint"), None);
+        assert_eq!(uri.to_string(), "aeron:udp?mtu=4096");
+    }
+
+    #[test]
+    fn should_build_channel_from_typed_params() {
+        let channel = ChannelUriStringBuilder::default()
+            .set_media(Media::Udp)
+            .set_control_endpoint("localhost:40124")
+            .set_control_mode(ControlMode::Dynamic)
+            .set_term_length(64 * 1024)
+            .set_initial_position(5, 7, 128)
+            .set_session_id(-12)
+            .set_linger(Duration::from_millis(5))
+            .set_reliable(false)
+            .set_rejoin(true)
+            .set_flow_control("min")
+            .build()
+            .unwrap();
+        assert_eq!(
+            channel,
+            "aeron:udp?control=localhost:40124|control-mode=dynamic|term-length=65536\
+             |init-term-id=5|term-id=7|term-offset=128|session-id=-12|linger=5000000\
+             |reliable=false|rejoin=true|fc=min"
+        );
+        assert_eq!(ChannelUri::parse(&channel).unwrap().to_string(), channel);
+
+        let spy = ChannelUriStringBuilder::default()
+            .set_spy(true)
+            .set_media(Media::Ipc)
+            .set_sparse(true)
+            .build()
+            .unwrap();
+        assert_eq!(spy, "aeron-spy:aeron:ipc?sparse=true");
+    }
+
+    #[test]
+    fn should_reject_invalid_builder_combinations() {
+        assert_eq!(
+            ChannelUriStringBuilder::default().set_mtu(1408).build(),
+            Err(ChannelUriError::MissingMedia)
+        );
+
+        let partial_position = ChannelUriStringBuilder::default()
+            .set_media(Media::Ipc)
+            .set_term_id(7)
+            .set_term_offset(0)
+            .build();
+        match partial_position {
+            Err(ChannelUriError::InvalidCombination(_)) => (),
+            other => panic!("Expected invalid combination, got {:?}", other),
+        }
+
+        for builder in &[
+            ChannelUriStringBuilder::default()
+                .set_media(Media::Ipc)
+                .set_endpoint("localhost:40123"),
+            ChannelUriStringBuilder::default()
+                .set_media(Media::Udp)
+                .set_control_mode(ControlMode::Dynamic),
+            ChannelUriStringBuilder::default()
+                .set_media(Media::Udp)
+                .set_term_length(64 * 1024)
+                .set_initial_position(0, 0, 64 * 1024),
+        ] {
+            match builder.build() {
+                Err(ChannelUriError::InvalidCombination(_)) => (),
+                other => panic!("Expected invalid combination, got {:?}", other),
+            }
+        }
+
+        match ChannelUriStringBuilder::default()
+            .set_media(Media::Udp)
+            .set_mtu(1000)
+            .build()
+        {
+            Err(ChannelUriError::InvalidValue { key, .. }) => assert_eq!(key, "mtu"),
+            other => panic!("Expected invalid mtu, got {:?}", other),
+        }
+    }
+}
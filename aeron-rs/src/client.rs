@@ -0,0 +1,208 @@
+//! Entry point for applications communicating with an Aeron media driver
+use crate::channel_uri::ChannelUri;
+use crate::client_conductor::{
+    await_response, lock_conductor, ClientConductor, ClientConductorAgent,
+};
+use crate::cnc_descriptor;
+use crate::concurrent::agent::{AgentInvoker, AgentRunner, AgentRunnerHandle};
+use crate::concurrent::idle_strategy::{IdleStrategy, SleepingIdleStrategy};
+use crate::concurrent::AtomicBuffer;
+use crate::context::{ClientContext, ImageHandler, DEFAULT_IDLE_SLEEP_MS};
+use crate::counter::Counter;
+use crate::image::Image;
+use crate::publication::Publication;
+use crate::subscription::Subscription;
+use crate::util::Result;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Connection to a media driver, used to create the resources an application
+/// needs to communicate through Aeron.
+///
+/// By default, the client conductor that communicates with the media driver runs on
+/// its own thread. If the context was configured to use an invoker instead, the
+/// application must call `conductor_invoker().invoke()` regularly.
+pub struct Aeron {
+    conductor: Arc<Mutex<ClientConductor>>,
+    conductor_runner: Option<AgentRunnerHandle>,
+    conductor_invoker: Option<AgentInvoker<ClientConductorAgent>>,
+    client_id: i64,
+    driver_timeout_ms: i64,
+}
+
+impl Aeron {
+    /// Connect to the media driver using the CnC file in the context's Aeron directory
+    pub fn connect(mut context: ClientContext) -> Result<Aeron> {
+        let cnc = cnc_descriptor::map_cnc_file(context.aeron_dir())?;
+        let driver_timeout_ms = context.driver_timeout_ms();
+        let use_invoker = context.use_conductor_agent_invoker();
+        let idle_strategy = context.idle_strategy.take();
+
+        let conductor = ClientConductor::new(cnc, context)?;
+        let client_id = conductor.client_id();
+        let conductor = Arc::new(Mutex::new(conductor));
+        let agent = ClientConductorAgent::new(conductor.clone());
+
+        let (conductor_runner, conductor_invoker) = if use_invoker {
+            (None, Some(AgentInvoker::new(agent)))
+        } else {
+            let idle_strategy: Box<dyn IdleStrategy + Send> = idle_strategy.unwrap_or_else(|| {
+                Box::new(SleepingIdleStrategy::new(Duration::from_millis(
+                    DEFAULT_IDLE_SLEEP_MS,
+                )))
+            });
+            (Some(AgentRunner::new(agent, idle_strategy).start()?), None)
+        };
+
+        Ok(Aeron {
+            conductor,
+            conductor_runner,
+            conductor_invoker,
+            client_id,
+            driver_timeout_ms,
+        })
+    }
+
+    /// Get the identifier the media driver uses for this client
+    pub fn client_id(&self) -> i64 {
+        self.client_id
+    }
+
+    /// Get the invoker used to drive the client conductor, if the context was configured
+    /// to use one. Invoking it polls for messages from the media driver, sends keepalives,
+    /// and checks that the media driver is still active.
+    pub fn conductor_invoker(&mut self) -> Option<&mut AgentInvoker<ClientConductorAgent>> {
+        self.conductor_invoker.as_mut()
+    }
+
+    /// Ask the media driver to allocate a counter, and wait for it to become available.
+    /// The key is an opaque buffer of at most `MAX_KEY_LENGTH` bytes, and the label
+    /// will be truncated by the media driver if longer than `MAX_LABEL_LENGTH`.
+    /// Fails with `AeronError::DriverError` if the media driver rejects the request.
+    pub fn add_counter(&self, type_id: i32, key: &[u8], label: &str) -> Result<Counter> {
+        let registration_id = self.lock_conductor()?.add_counter(type_id, key, label)?;
+        let counter_id = await_response(
+            &self.conductor,
+            registration_id,
+            self.driver_timeout_ms,
+            |conductor| conductor.poll_counter(registration_id),
+        )?;
+
+        let values_buffer = {
+            let conductor = self.lock_conductor()?;
+            let values_buffer = conductor.counters_reader().values_buffer();
+            // SAFETY: Counter values are only accessed through `AtomicBuffer` operations
+            unsafe { values_buffer.view(0, values_buffer.capacity()) }?
+        };
+        Counter::new(
+            registration_id,
+            counter_id,
+            values_buffer,
+            self.conductor.clone(),
+        )
+    }
+
+    /// Ask the media driver to add a publication for a channel and stream, and wait for
+    /// it to become available. Fails with `AeronError::ChannelUri` if the channel isn't
+    /// a valid Aeron URI, or `AeronError::DriverError` if the media driver rejects it.
+    pub fn add_publication(&self, channel: &str, stream_id: i32) -> Result<Publication> {
+        ChannelUri::parse(channel)?;
+
+        let correlation_id = self.lock_conductor()?.add_publication(channel, stream_id)?;
+        let ready = await_response(
+            &self.conductor,
+            correlation_id,
+            self.driver_timeout_ms,
+            |conductor| conductor.poll_publication(correlation_id),
+        )?;
+
+        Ok(Publication::new(
+            channel,
+            ready,
+            self.conductor.clone(),
+            self.driver_timeout_ms,
+        ))
+    }
+
+    /// Ask the media driver to add a subscription for a channel and stream, and wait for
+    /// it to become available. Fails with `AeronError::ChannelUri` if the channel isn't
+    /// a valid Aeron URI, or `AeronError::DriverError` if the media driver rejects it.
+    /// Image availability is reported to the handlers configured in the client context.
+    pub fn add_subscription(&self, channel: &str, stream_id: i32) -> Result<Subscription> {
+        self.subscribe(channel, stream_id, None, None)
+    }
+
+    /// Ask the media driver to add a subscription for a channel and stream, and wait for
+    /// it to become available. Images becoming available to or being removed from the
+    /// subscription are reported to the given handlers instead of those configured in
+    /// the client context. Handlers run without the client conductor lock held, and
+    /// may call back into the client.
+    pub fn add_subscription_with_handlers<F, G>(
+        &self,
+        channel: &str,
+        stream_id: i32,
+        available_image_handler: F,
+        unavailable_image_handler: G,
+    ) -> Result<Subscription>
+    where
+        F: FnMut(&Image) + Send + 'static,
+        G: FnMut(&Image) + Send + 'static,
+    {
+        self.subscribe(
+            channel,
+            stream_id,
+            Some(Box::new(available_image_handler)),
+            Some(Box::new(unavailable_image_handler)),
+        )
+    }
+
+    fn subscribe(
+        &self,
+        channel: &str,
+        stream_id: i32,
+        available_image_handler: Option<ImageHandler>,
+        unavailable_image_handler: Option<ImageHandler>,
+    ) -> Result<Subscription> {
+        ChannelUri::parse(channel)?;
+
+        let correlation_id = self.lock_conductor()?.add_subscription(
+            channel,
+            stream_id,
+            available_image_handler,
+            unavailable_image_handler,
+        )?;
+        let channel_status_indicator_id = await_response(
+            &self.conductor,
+            correlation_id,
+            self.driver_timeout_ms,
+            |conductor| conductor.poll_subscription(correlation_id),
+        )?;
+
+        Ok(Subscription::new(
+            channel,
+            stream_id,
+            correlation_id,
+            channel_status_indicator_id,
+            self.conductor.clone(),
+            self.driver_timeout_ms,
+        ))
+    }
+
+    fn lock_conductor(&self) -> Result<MutexGuard<'_, ClientConductor>> {
+        lock_conductor(&self.conductor)
+    }
+}
+
+impl Drop for Aeron {
+    fn drop(&mut self) {
+        if let Some(mut runner) = self.conductor_runner.take() {
+            let _ = runner.close();
+        }
+        if let Some(mut invoker) = self.conductor_invoker.take() {
+            let _ = invoker.close();
+        }
+        if let Ok(mut conductor) = self.conductor.lock() {
+            let _ = conductor.close();
+        }
+    }
+}
@@ -0,0 +1,903 @@
+//! Client-side processing of the command-and-control protocol. The conductor sends
+//! commands to the media driver and handles the responses it broadcasts to clients.
+use crate::cnc_descriptor;
+use crate::cnc_descriptor::{MetaDataDefinition, CNC_VERSION, META_DATA_LENGTH};
+use crate::command::counter_update::CounterUpdateDefn;
+use crate::command::error_response::{DriverErrorResponse, ErrorResponseDefn};
+use crate::command::flyweight::Flyweight;
+use crate::command::image_buffers_ready::ImageBuffersReadyDefn;
+use crate::command::image_message::ImageMessageDefn;
+use crate::command::operation_succeeded::OperationSucceededDefn;
+use crate::command::publication_buffers_ready::PublicationBuffersReadyDefn;
+use crate::command::subscription_ready::SubscriptionReadyDefn;
+use crate::concurrent::agent::Agent;
+use crate::concurrent::broadcast::{BroadcastReceiver, CopyBroadcastReceiver};
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::ringbuffer::ManyToOneRingBuffer;
+use crate::concurrent::{AtomicBuffer, MappedBuffer};
+use crate::context::{ClientContext, CounterHandler, ImageHandler};
+use crate::control_protocol::DriverResponse;
+use crate::driver_proxy::DriverProxy;
+use crate::image::Image;
+use crate::util::time::epoch_millis;
+use crate::util::{AeronError, IndexT, Result};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Interval at which keepalive messages are sent to the media driver
+pub const KEEPALIVE_INTERVAL_MS: i64 = 500;
+
+// Progress of a request waiting on the media driver
+enum RegistrationStatus<T> {
+    AwaitingMediaDriver,
+    Registered(T),
+    Errored(DriverErrorResponse),
+}
+
+// Take the result of a request if the media driver has responded to it
+fn poll_registration<T>(
+    pending: &mut HashMap<i64, RegistrationStatus<T>>,
+    correlation_id: i64,
+) -> Result<Option<T>> {
+    match pending.remove(&correlation_id) {
+        Some(RegistrationStatus::Registered(value)) => Ok(Some(value)),
+        Some(RegistrationStatus::Errored(error)) => Err(AeronError::DriverError(error)),
+        Some(RegistrationStatus::AwaitingMediaDriver) => {
+            pending.insert(correlation_id, RegistrationStatus::AwaitingMediaDriver);
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Details of a publication the media driver has added for this client
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicationReady {
+    /// Registration identifier of this client's link to the publication, which is the
+    /// correlation identifier of the request that added it
+    pub registration_id: i64,
+    /// Registration identifier the media driver gave the publication when first added.
+    /// Differs from `registration_id` when a channel and stream are added more than once.
+    pub original_registration_id: i64,
+    /// Session identifier of the publication
+    pub session_id: i32,
+    /// Stream identifier of the publication
+    pub stream_id: i32,
+    /// Identifier of the counter limiting how far the publication may advance
+    pub position_limit_counter_id: i32,
+    /// Identifier of the counter tracking the status of the send channel
+    pub channel_status_indicator_id: i32,
+    /// Name of the file holding the publication's log buffers
+    pub log_file_name: String,
+}
+
+// Counter and image handlers are called once the conductor lock is released, so
+// they're shared between the conductor and whichever thread dispatches the notification.
+type SharedCounterHandler = Arc<Mutex<CounterHandler>>;
+type SharedImageHandler = Arc<Mutex<ImageHandler>>;
+
+fn share_handler<H>(handler: Option<H>) -> Option<Arc<Mutex<H>>> {
+    handler.map(|handler| Arc::new(Mutex::new(handler)))
+}
+
+// Images received by a subscription added by this client, and the handlers to
+// notify when they change
+struct SubscriptionImages {
+    images: HashMap<i64, Image>,
+    available_image_handler: Option<SharedImageHandler>,
+    unavailable_image_handler: Option<SharedImageHandler>,
+}
+
+/// Change in counter or image availability, waiting to be passed to its handler once
+/// the client conductor lock has been released
+pub(crate) enum Notification {
+    Counter {
+        handler: SharedCounterHandler,
+        counters_reader: Arc<CountersReader<MappedBuffer>>,
+        registration_id: i64,
+        counter_id: i32,
+    },
+    Image {
+        handler: SharedImageHandler,
+        image: Image,
+    },
+}
+
+thread_local! {
+    // Set while this thread runs notification handlers. Notifications raised by calls
+    // a handler makes into the client are left for the outermost dispatch.
+    // `const` initialisers aren't available on the minimum supported Rust version
+    #[allow(clippy::missing_const_for_thread_local)]
+    static DISPATCHING: Cell<bool> = Cell::new(false);
+}
+
+// Clears the dispatch flag even if a handler panics
+struct DispatchGuard;
+
+impl Drop for DispatchGuard {
+    fn drop(&mut self) {
+        DISPATCHING.with(|dispatching| dispatching.set(false));
+    }
+}
+
+// A handler that panicked poisons its lock; later notifications are still delivered
+fn lock_handler<H>(handler: &Mutex<H>) -> MutexGuard<'_, H> {
+    handler
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Pass notifications to their handlers. Must be called without holding the client
+/// conductor lock, so that handlers are free to call back into the client.
+pub(crate) fn dispatch_notifications(notifications: Vec<Notification>) {
+    if notifications.is_empty() {
+        return;
+    }
+
+    DISPATCHING.with(|dispatching| dispatching.set(true));
+    let _guard = DispatchGuard;
+    for notification in notifications {
+        match notification {
+            Notification::Counter {
+                handler,
+                counters_reader,
+                registration_id,
+                counter_id,
+            } => (*lock_handler(&handler))(&counters_reader, registration_id, counter_id),
+            Notification::Image { handler, image } => (*lock_handler(&handler))(&image),
+        }
+    }
+}
+
+// Take the details of a newly available image from the media driver's response
+fn available_image(
+    ready: &Flyweight<Vec<u8>, ImageBuffersReadyDefn>,
+    counters_reader: &CountersReader<MappedBuffer>,
+) -> Result<Image> {
+    Ok(Image {
+        correlation_id: ready.correlation_id(),
+        subscription_registration_id: ready.subscriber_registration_id(),
+        session_id: ready.session_id(),
+        stream_id: ready.stream_id(),
+        source_identity: ready.source_identity()?.to_string(),
+        // The subscriber position starts at the point the subscription joined
+        join_position: counters_reader.counter_value(ready.subscriber_position_id())?,
+        subscriber_position_id: ready.subscriber_position_id(),
+        log_file_name: ready.log_file_name()?.to_string(),
+    })
+}
+
+/// Handles all communication with the media driver on behalf of an Aeron client.
+pub struct ClientConductor {
+    driver_proxy: DriverProxy<MappedBuffer>,
+    to_clients: CopyBroadcastReceiver<MappedBuffer>,
+    counters_reader: Arc<CountersReader<MappedBuffer>>,
+    pending_counters: HashMap<i64, RegistrationStatus<i32>>,
+    pending_publications: HashMap<i64, RegistrationStatus<PublicationReady>>,
+    pending_subscriptions: HashMap<i64, RegistrationStatus<i32>>,
+    pending_operations: HashMap<i64, RegistrationStatus<()>>,
+    subscriptions: HashMap<i64, SubscriptionImages>,
+    // Commands nobody waits on, whose errors go to the error handler
+    async_commands: HashSet<i64>,
+    error_handler: Box<dyn FnMut(AeronError) + Send>,
+    available_counter_handler: Option<SharedCounterHandler>,
+    unavailable_counter_handler: Option<SharedCounterHandler>,
+    available_image_handler: Option<SharedImageHandler>,
+    unavailable_image_handler: Option<SharedImageHandler>,
+    notifications: Vec<Notification>,
+    driver_timeout_ms: i64,
+    time_of_last_keepalive_ms: i64,
+    is_closed: bool,
+}
+
+impl ClientConductor {
+    /// Create a new conductor over a mapped CnC file. Fails if the CnC version doesn't
+    /// match, or if the media driver isn't active.
+    pub fn new(cnc: MappedBuffer, context: ClientContext) -> Result<Self> {
+        let metadata = cnc.overlay::<MetaDataDefinition>(0)?;
+        if metadata.cnc_version != CNC_VERSION {
+            return Err(AeronError::CncVersionMismatch {
+                expected: CNC_VERSION,
+                actual: metadata.cnc_version,
+            });
+        }
+
+        // SAFETY: The ring buffer and broadcast buffer only access the CnC file
+        // through `AtomicBuffer` operations.
+        let to_driver =
+            unsafe { cnc.view(META_DATA_LENGTH as IndexT, metadata.to_driver_buffer_length) }?;
+        let to_clients = unsafe {
+            cnc.view(
+                metadata.to_client_buffer_offset() as IndexT,
+                metadata.to_client_buffer_length,
+            )
+        }?;
+
+        let driver_proxy = DriverProxy::new(ManyToOneRingBuffer::new(to_driver)?);
+        let now = epoch_millis();
+        let elapsed_ms = now - driver_proxy.time_of_last_driver_keepalive();
+        if elapsed_ms > context.driver_timeout_ms() {
+            return Err(AeronError::DriverTimeout {
+                elapsed_ms,
+                timeout_ms: context.driver_timeout_ms(),
+            });
+        }
+
+        Ok(ClientConductor {
+            driver_proxy,
+            // Messages for other clients may be overwritten before we see them,
+            // and we only care about the most recent state anyway.
+            to_clients: CopyBroadcastReceiver::new(BroadcastReceiver::new(to_clients)?)
+                .set_loss_handler(|_laps| {}),
+            counters_reader: Arc::new(cnc_descriptor::counters_reader(&cnc)?),
+            pending_counters: HashMap::new(),
+            pending_publications: HashMap::new(),
+            pending_subscriptions: HashMap::new(),
+            pending_operations: HashMap::new(),
+            subscriptions: HashMap::new(),
+            async_commands: HashSet::new(),
+            driver_timeout_ms: context.driver_timeout_ms(),
+            error_handler: context
+                .error_handler
+                .unwrap_or_else(|| Box::new(|error| eprintln!("Aeron client error: {}", error))),
+            available_counter_handler: share_handler(context.available_counter_handler),
+            unavailable_counter_handler: share_handler(context.unavailable_counter_handler),
+            available_image_handler: share_handler(context.available_image_handler),
+            unavailable_image_handler: share_handler(context.unavailable_image_handler),
+            notifications: Vec::new(),
+            time_of_last_keepalive_ms: 0,
+            is_closed: false,
+        })
+    }
+
+    /// Get the identifier the media driver uses for this client
+    pub fn client_id(&self) -> i64 {
+        self.driver_proxy.client_id()
+    }
+
+    /// Get a reader over the counters maintained by the media driver
+    pub fn counters_reader(&self) -> &CountersReader<MappedBuffer> {
+        &self.counters_reader
+    }
+
+    /// Check whether this conductor has been closed
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Perform a single round of work: send a keepalive to the media driver if one
+    /// is due, and handle the next message from the media driver if one is available.
+    /// Returns the amount of work done. Fails if the media driver has gone inactive.
+    pub fn do_work(&mut self) -> Result<i32> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let mut work_count = 0;
+        let now = epoch_millis();
+        if now - self.time_of_last_keepalive_ms >= KEEPALIVE_INTERVAL_MS {
+            let elapsed_ms = now - self.driver_proxy.time_of_last_driver_keepalive();
+            if elapsed_ms > self.driver_timeout_ms {
+                self.is_closed = true;
+                return Err(AeronError::DriverTimeout {
+                    elapsed_ms,
+                    timeout_ms: self.driver_timeout_ms,
+                });
+            }
+
+            self.driver_proxy.send_client_keepalive()?;
+            self.time_of_last_keepalive_ms = now;
+            work_count += 1;
+        }
+
+        let ClientConductor {
+            ref mut to_clients,
+            ref counters_reader,
+            ref mut pending_counters,
+            ref mut pending_publications,
+            ref mut pending_subscriptions,
+            ref mut pending_operations,
+            ref mut subscriptions,
+            ref mut async_commands,
+            ref mut error_handler,
+            ref available_counter_handler,
+            ref unavailable_counter_handler,
+            ref available_image_handler,
+            ref unavailable_image_handler,
+            ref mut notifications,
+            ..
+        } = *self;
+        work_count += to_clients.receive(|msg_type_id, msg| {
+            let response = match DriverResponse::try_from(msg_type_id as u32) {
+                Ok(response) => response,
+                Err(_) => return,
+            };
+
+            match response {
+                DriverResponse::OnCounterReady | DriverResponse::OnUnavailableCounter => {
+                    let update = match Flyweight::new::<CounterUpdateDefn>(msg.to_vec(), 0) {
+                        Ok(update) => update,
+                        Err(_) => return,
+                    };
+                    let correlation_id = update.correlation_id();
+                    let counter_id = update.counter_id();
+
+                    let handler = if response == DriverResponse::OnCounterReady {
+                        if let Some(pending) = pending_counters.get_mut(&correlation_id) {
+                            *pending = RegistrationStatus::Registered(counter_id);
+                        }
+                        available_counter_handler
+                    } else {
+                        unavailable_counter_handler
+                    };
+                    if let Some(handler) = handler {
+                        notifications.push(Notification::Counter {
+                            handler: handler.clone(),
+                            counters_reader: counters_reader.clone(),
+                            registration_id: correlation_id,
+                            counter_id,
+                        });
+                    }
+                }
+                DriverResponse::OnError => {
+                    let error = match Flyweight::new::<ErrorResponseDefn>(msg.to_vec(), 0)
+                        .and_then(|response| response.to_response())
+                    {
+                        Ok(error) => error,
+                        Err(_) => return,
+                    };
+                    let correlation_id = error.offending_command_correlation_id;
+
+                    // Errors for commands sent by other clients are ignored
+                    if let Some(pending) = pending_counters.get_mut(&correlation_id) {
+                        *pending = RegistrationStatus::Errored(error);
+                    } else if let Some(pending) = pending_publications.get_mut(&correlation_id) {
+                        *pending = RegistrationStatus::Errored(error);
+                    } else if let Some(pending) = pending_subscriptions.get_mut(&correlation_id) {
+                        *pending = RegistrationStatus::Errored(error);
+                    } else if let Some(pending) = pending_operations.get_mut(&correlation_id) {
+                        *pending = RegistrationStatus::Errored(error);
+                    } else if async_commands.remove(&correlation_id) {
+                        error_handler(AeronError::DriverError(error));
+                    }
+                }
+                DriverResponse::OnOperationSuccess => {
+                    if let Ok(success) = Flyweight::new::<OperationSucceededDefn>(msg.to_vec(), 0) {
+                        let correlation_id = success.correlation_id();
+                        if let Some(pending) = pending_operations.get_mut(&correlation_id) {
+                            *pending = RegistrationStatus::Registered(());
+                        } else {
+                            async_commands.remove(&correlation_id);
+                        }
+                    }
+                }
+                DriverResponse::OnSubscriptionReady => {
+                    if let Ok(ready) = Flyweight::new::<SubscriptionReadyDefn>(msg.to_vec(), 0) {
+                        if let Some(pending) =
+                            pending_subscriptions.get_mut(&ready.correlation_id())
+                        {
+                            *pending =
+                                RegistrationStatus::Registered(ready.channel_status_indicator_id());
+                        }
+                    }
+                }
+                DriverResponse::OnAvailableImage => {
+                    let ready = match Flyweight::new::<ImageBuffersReadyDefn>(msg.to_vec(), 0) {
+                        Ok(ready) => ready,
+                        Err(_) => return,
+                    };
+                    // Images for other clients' subscriptions are ignored
+                    let subscription =
+                        match subscriptions.get_mut(&ready.subscriber_registration_id()) {
+                            Some(subscription) => subscription,
+                            None => return,
+                        };
+                    if subscription.images.contains_key(&ready.correlation_id()) {
+                        return;
+                    }
+                    let image = match available_image(&ready, counters_reader) {
+                        Ok(image) => image,
+                        Err(_) => return,
+                    };
+
+                    let handler = subscription
+                        .available_image_handler
+                        .as_ref()
+                        .or(available_image_handler.as_ref());
+                    if let Some(handler) = handler {
+                        notifications.push(Notification::Image {
+                            handler: handler.clone(),
+                            image: image.clone(),
+                        });
+                    }
+                    subscription.images.insert(image.correlation_id, image);
+                }
+                DriverResponse::OnUnavailableImage => {
+                    let message = match Flyweight::new::<ImageMessageDefn>(msg.to_vec(), 0) {
+                        Ok(message) => message,
+                        Err(_) => return,
+                    };
+                    let subscription =
+                        match subscriptions.get_mut(&message.subscription_registration_id()) {
+                            Some(subscription) => subscription,
+                            None => return,
+                        };
+                    let image = match subscription.images.remove(&message.correlation_id()) {
+                        Some(image) => image,
+                        None => return,
+                    };
+
+                    let handler = subscription
+                        .unavailable_image_handler
+                        .as_ref()
+                        .or(unavailable_image_handler.as_ref());
+                    if let Some(handler) = handler {
+                        notifications.push(Notification::Image {
+                            handler: handler.clone(),
+                            image,
+                        });
+                    }
+                }
+                DriverResponse::OnPublicationReady => {
+                    let ready = match Flyweight::new::<PublicationBuffersReadyDefn>(msg.to_vec(), 0)
+                    {
+                        Ok(ready) => ready,
+                        Err(_) => return,
+                    };
+                    let correlation_id = ready.correlation_id();
+                    if let Some(pending) = pending_publications.get_mut(&correlation_id) {
+                        let log_file_name = match ready.log_file_name() {
+                            Ok(log_file_name) => log_file_name.to_string(),
+                            Err(_) => return,
+                        };
+                        *pending = RegistrationStatus::Registered(PublicationReady {
+                            registration_id: correlation_id,
+                            original_registration_id: ready.registration_id(),
+                            session_id: ready.session_id(),
+                            stream_id: ready.stream_id(),
+                            position_limit_counter_id: ready.position_limit_counter_id(),
+                            channel_status_indicator_id: ready.channel_status_indicator_id(),
+                            log_file_name,
+                        });
+                    }
+                }
+                // Remaining responses are for resources we don't yet support
+                _ => (),
+            }
+        })?;
+
+        Ok(work_count)
+    }
+
+    /// Request the media driver add a counter. Returns the registration identifier
+    /// of the counter; use `poll_counter` to check when it's ready.
+    pub fn add_counter(&mut self, type_id: i32, key: &[u8], label: &str) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let registration_id = self.driver_proxy.add_counter(type_id, key, label)?;
+        self.pending_counters
+            .insert(registration_id, RegistrationStatus::AwaitingMediaDriver);
+        Ok(registration_id)
+    }
+
+    /// Check whether a counter requested by `add_counter` is ready. Returns the counter
+    /// identifier once the media driver has allocated it, or the error response if the
+    /// media driver rejected the request.
+    pub fn poll_counter(&mut self, registration_id: i64) -> Result<Option<i32>> {
+        poll_registration(&mut self.pending_counters, registration_id)
+    }
+
+    /// Request the media driver remove a counter added by this client
+    pub fn remove_counter(&mut self, registration_id: i64) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        let correlation_id = self.driver_proxy.remove_counter(registration_id)?;
+        self.async_commands.insert(correlation_id);
+        Ok(())
+    }
+
+    /// Request the media driver add a publication. Returns the correlation identifier
+    /// of the request; use `poll_publication` to check when it's ready.
+    pub fn add_publication(&mut self, channel: &str, stream_id: i32) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self.driver_proxy.add_publication(channel, stream_id)?;
+        self.pending_publications
+            .insert(correlation_id, RegistrationStatus::AwaitingMediaDriver);
+        Ok(correlation_id)
+    }
+
+    /// Check whether a publication requested by `add_publication` is ready. Returns the
+    /// publication details once the media driver has added it, or the error response
+    /// if the media driver rejected the request.
+    pub fn poll_publication(&mut self, correlation_id: i64) -> Result<Option<PublicationReady>> {
+        poll_registration(&mut self.pending_publications, correlation_id)
+    }
+
+    /// Request the media driver remove a publication added by this client
+    pub fn remove_publication(&mut self, registration_id: i64) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        let correlation_id = self.driver_proxy.remove_publication(registration_id)?;
+        self.async_commands.insert(correlation_id);
+        Ok(())
+    }
+
+    /// Request the media driver add a destination to a multi-destination-cast publication.
+    /// Returns the correlation identifier of the request; use `poll_operation` to check
+    /// when it has completed.
+    pub fn add_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self
+            .driver_proxy
+            .add_destination(registration_id, channel)?;
+        Ok(self.track_operation(correlation_id))
+    }
+
+    /// Request the media driver remove a destination from a multi-destination-cast
+    /// publication. Returns the correlation identifier of the request; use
+    /// `poll_operation` to check when it has completed.
+    pub fn remove_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self
+            .driver_proxy
+            .remove_destination(registration_id, channel)?;
+        Ok(self.track_operation(correlation_id))
+    }
+
+    /// Request the media driver add a subscription. Returns the correlation identifier
+    /// of the request; use `poll_subscription` to check when it's ready. Image handlers
+    /// given here take priority over those configured for the client.
+    pub fn add_subscription(
+        &mut self,
+        channel: &str,
+        stream_id: i32,
+        available_image_handler: Option<ImageHandler>,
+        unavailable_image_handler: Option<ImageHandler>,
+    ) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self.driver_proxy.add_subscription(channel, stream_id)?;
+        self.pending_subscriptions
+            .insert(correlation_id, RegistrationStatus::AwaitingMediaDriver);
+        // Images may become available before the subscription is polled
+        self.subscriptions.insert(
+            correlation_id,
+            SubscriptionImages {
+                images: HashMap::new(),
+                available_image_handler: share_handler(available_image_handler),
+                unavailable_image_handler: share_handler(unavailable_image_handler),
+            },
+        );
+        Ok(correlation_id)
+    }
+
+    /// Check whether a subscription requested by `add_subscription` is ready. Returns
+    /// the identifier of the subscription's channel status counter once the media driver
+    /// has added it, or the error response if the media driver rejected the request.
+    pub fn poll_subscription(&mut self, correlation_id: i64) -> Result<Option<i32>> {
+        let result = poll_registration(&mut self.pending_subscriptions, correlation_id);
+        if result.is_err() {
+            self.subscriptions.remove(&correlation_id);
+        }
+        result
+    }
+
+    /// Get the images currently available to a subscription added by this client
+    pub fn images(&self, registration_id: i64) -> Vec<Image> {
+        self.subscriptions
+            .get(&registration_id)
+            .map(|subscription| subscription.images.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Request the media driver remove a subscription added by this client. Images
+    /// still available to the subscription are reported as unavailable.
+    pub fn remove_subscription(&mut self, registration_id: i64) -> Result<()> {
+        if let Some(subscription) = self.subscriptions.remove(&registration_id) {
+            let handler = subscription
+                .unavailable_image_handler
+                .or_else(|| self.unavailable_image_handler.clone());
+            if let Some(handler) = handler {
+                for (_, image) in subscription.images {
+                    self.notifications.push(Notification::Image {
+                        handler: handler.clone(),
+                        image,
+                    });
+                }
+            }
+        }
+
+        if self.is_closed {
+            return Ok(());
+        }
+
+        let correlation_id = self.driver_proxy.remove_subscription(registration_id)?;
+        self.async_commands.insert(correlation_id);
+        Ok(())
+    }
+
+    /// Request the media driver add a destination to a multi-destination subscription.
+    /// Returns the correlation identifier of the request; use `poll_operation` to check
+    /// when it has completed.
+    pub fn add_rcv_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self
+            .driver_proxy
+            .add_rcv_destination(registration_id, channel)?;
+        Ok(self.track_operation(correlation_id))
+    }
+
+    /// Request the media driver remove a destination from a multi-destination
+    /// subscription. Returns the correlation identifier of the request; use
+    /// `poll_operation` to check when it has completed.
+    pub fn remove_rcv_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("client conductor is closed"));
+        }
+
+        let correlation_id = self
+            .driver_proxy
+            .remove_rcv_destination(registration_id, channel)?;
+        Ok(self.track_operation(correlation_id))
+    }
+
+    /// Check whether an operation such as `add_destination` has completed. Returns true
+    /// once the media driver reports success, or the error response if the media driver
+    /// rejected the request.
+    pub fn poll_operation(&mut self, correlation_id: i64) -> Result<bool> {
+        Ok(poll_registration(&mut self.pending_operations, correlation_id)?.is_some())
+    }
+
+    /// Stop waiting for a request. If the media driver later rejects an abandoned
+    /// operation, the error is passed to the client's error handler.
+    pub fn cancel_request(&mut self, correlation_id: i64) {
+        self.pending_counters.remove(&correlation_id);
+        self.pending_publications.remove(&correlation_id);
+        if self.pending_subscriptions.remove(&correlation_id).is_some() {
+            self.subscriptions.remove(&correlation_id);
+        }
+        if self.pending_operations.remove(&correlation_id).is_some() {
+            self.async_commands.insert(correlation_id);
+        }
+    }
+
+    /// Take the notifications raised since the last call, to be dispatched once the
+    /// conductor lock is released. Returns nothing while this thread is already
+    /// dispatching, so notifications are never passed to a handler that's still running.
+    pub(crate) fn take_notifications(&mut self) -> Vec<Notification> {
+        if DISPATCHING.with(|dispatching| dispatching.get()) {
+            Vec::new()
+        } else {
+            self.notifications.drain(..).collect()
+        }
+    }
+
+    fn track_operation(&mut self, correlation_id: i64) -> i64 {
+        self.pending_operations
+            .insert(correlation_id, RegistrationStatus::AwaitingMediaDriver);
+        correlation_id
+    }
+
+    /// Report an error to the handler configured for this client
+    pub fn handle_error(&mut self, error: AeronError) {
+        (self.error_handler)(error)
+    }
+
+    /// Let the media driver know this client is closing. No further commands
+    /// will be sent to the media driver.
+    pub fn close(&mut self) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        self.is_closed = true;
+        self.driver_proxy.client_close()
+    }
+}
+
+pub(crate) fn lock_conductor(
+    conductor: &Mutex<ClientConductor>,
+) -> Result<MutexGuard<'_, ClientConductor>> {
+    conductor
+        .lock()
+        .map_err(|_| AeronError::IllegalState("client conductor lock is poisoned"))
+}
+
+// Wait for the media driver to respond to a request, driving the conductor while
+// waiting so responses are seen even when no other thread is invoking it.
+pub(crate) fn await_response<T, F>(
+    conductor: &Mutex<ClientConductor>,
+    correlation_id: i64,
+    timeout_ms: i64,
+    mut poll: F,
+) -> Result<T>
+where
+    F: FnMut(&mut ClientConductor) -> Result<Option<T>>,
+{
+    let deadline = epoch_millis() + timeout_ms;
+
+    loop {
+        let (response, notifications) = {
+            let mut conductor = lock_conductor(conductor)?;
+            let work = conductor.do_work();
+            let notifications = conductor.take_notifications();
+            let response = work.and_then(|_| poll(&mut conductor));
+            if let Ok(None) = response {
+                if epoch_millis() > deadline {
+                    conductor.cancel_request(correlation_id);
+                    drop(conductor);
+                    dispatch_notifications(notifications);
+                    return Err(AeronError::RequestTimeout {
+                        correlation_id,
+                        timeout_ms,
+                    });
+                }
+            }
+            (response, notifications)
+        };
+        dispatch_notifications(notifications);
+
+        if let Some(response) = response? {
+            return Ok(response);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Command sent to the media driver that completes asynchronously, such as adding
+/// a destination to a publication. If dropped before completing, any error from the
+/// media driver is passed to the client's error handler instead.
+pub struct PendingOperation {
+    correlation_id: i64,
+    conductor: Arc<Mutex<ClientConductor>>,
+    timeout_ms: i64,
+    outcome: Option<Result<()>>,
+}
+
+impl PendingOperation {
+    pub(crate) fn new(
+        correlation_id: i64,
+        conductor: Arc<Mutex<ClientConductor>>,
+        timeout_ms: i64,
+    ) -> Self {
+        PendingOperation {
+            correlation_id,
+            conductor,
+            timeout_ms,
+            outcome: None,
+        }
+    }
+
+    /// Get the correlation identifier of the command sent to the media driver
+    pub fn correlation_id(&self) -> i64 {
+        self.correlation_id
+    }
+
+    /// Check whether the media driver has completed the operation, without waiting.
+    /// Returns `AeronError::DriverError` if the media driver rejected it. Responses
+    /// are only seen when the client conductor does work, so clients using an invoker
+    /// must keep invoking it.
+    pub fn poll(&mut self) -> Result<bool> {
+        if let Some(ref outcome) = self.outcome {
+            return outcome.clone().map(|_| true);
+        }
+
+        match lock_conductor(&self.conductor)?.poll_operation(self.correlation_id) {
+            Ok(false) => Ok(false),
+            outcome => {
+                let outcome = outcome.map(|_| ());
+                self.outcome = Some(outcome.clone());
+                outcome.map(|_| true)
+            }
+        }
+    }
+
+    /// Wait for the media driver to complete the operation. Fails with
+    /// `AeronError::DriverError` if the media driver rejected it, or
+    /// `AeronError::RequestTimeout` if there was no response within the driver timeout.
+    pub fn wait(mut self) -> Result<()> {
+        if let Some(outcome) = self.outcome.take() {
+            // Mark the operation as resolved so dropping it has no effect
+            self.outcome = Some(Ok(()));
+            return outcome;
+        }
+
+        let correlation_id = self.correlation_id;
+        let outcome = await_response(
+            &self.conductor,
+            correlation_id,
+            self.timeout_ms,
+            |conductor| {
+                Ok(if conductor.poll_operation(correlation_id)? {
+                    Some(())
+                } else {
+                    None
+                })
+            },
+        );
+        self.outcome = Some(Ok(()));
+        outcome
+    }
+}
+
+impl Drop for PendingOperation {
+    fn drop(&mut self) {
+        if self.outcome.is_none() {
+            if let Ok(mut conductor) = self.conductor.lock() {
+                conductor.cancel_request(self.correlation_id);
+            }
+        }
+    }
+}
+
+/// Agent performing the duty cycle of a client conductor shared with an `Aeron` client
+pub struct ClientConductorAgent {
+    conductor: Arc<Mutex<ClientConductor>>,
+}
+
+impl ClientConductorAgent {
+    /// Create an agent for a shared client conductor
+    pub fn new(conductor: Arc<Mutex<ClientConductor>>) -> Self {
+        ClientConductorAgent { conductor }
+    }
+}
+
+impl Agent for ClientConductorAgent {
+    /// Perform a duty cycle of the conductor. Errors are passed to the client's error
+    /// handler. Once the conductor is closed, there is no more work to do.
+    fn do_work(&mut self) -> Result<i32> {
+        let (work_count, notifications) = {
+            let mut conductor = lock_conductor(&self.conductor)?;
+            if conductor.is_closed() {
+                return Ok(0);
+            }
+            let work_count = match conductor.do_work() {
+                Ok(work_count) => work_count,
+                Err(error) => {
+                    conductor.handle_error(error);
+                    0
+                }
+            };
+            (work_count, conductor.take_notifications())
+        };
+
+        // Handlers may call back into the client, so run them without the lock held
+        dispatch_notifications(notifications);
+        Ok(work_count)
+    }
+
+    fn on_close(&mut self) -> Result<()> {
+        self.conductor
+            .lock()
+            .map_err(|_| AeronError::IllegalState("client conductor lock is poisoned"))?
+            .close()
+    }
+
+    fn role_name(&self) -> String {
+        "aeron-client-conductor".to_string()
+    }
+}
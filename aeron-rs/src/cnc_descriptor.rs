@@ -19,8 +19,13 @@
 //! +-----------------------------+
 //! ```
 
-use crate::util::bit;
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::{AtomicBuffer, MappedBuffer};
+use crate::util::{bit, IndexT, Result};
+use memmap::MmapOptions;
+use std::fs::OpenOptions;
 use std::mem::size_of;
+use std::path::Path;
 
 /// The CnC file metadata header. Layout:
 ///
@@ -52,16 +57,48 @@ use std::mem::size_of;
 /// ```
 #[repr(C, align(4))]
 pub struct MetaDataDefinition {
-    cnc_version: i32,
+    /// Version of the CnC file format used by the media driver
+    pub cnc_version: i32,
     /// Size of the buffer containing data going to the media driver
     pub to_driver_buffer_length: i32,
-    _to_client_buffer_length: i32,
-    _counter_metadata_buffer_length: i32,
-    _counter_values_buffer_length: i32,
-    _error_log_buffer_length: i32,
-    _client_liveness_timeout: i64,
-    _start_timestamp: i64,
-    _pid: i64,
+    /// Size of the buffer containing data going to the clients
+    pub to_client_buffer_length: i32,
+    /// Size of the buffer containing counters metadata
+    pub counter_metadata_buffer_length: i32,
+    /// Size of the buffer containing counters values
+    pub counter_values_buffer_length: i32,
+    /// Size of the buffer containing the distinct error log
+    pub error_log_buffer_length: i32,
+    /// Time (in nanoseconds) a client may go without a keepalive before the driver removes it
+    pub client_liveness_timeout: i64,
+    /// Time the media driver started, as milliseconds since 1 Jan 1970, UTC
+    pub start_timestamp: i64,
+    /// Process identifier of the media driver
+    pub pid: i64,
+}
+
+impl MetaDataDefinition {
+    /// Offset from the start of the CnC file to the to-clients buffer
+    pub fn to_client_buffer_offset(&self) -> usize {
+        META_DATA_LENGTH + self.to_driver_buffer_length as usize
+    }
+
+    /// Offset from the start of the CnC file to the counters metadata buffer
+    pub fn counter_metadata_buffer_offset(&self) -> usize {
+        META_DATA_LENGTH
+            + self.to_driver_buffer_length as usize
+            + self.to_client_buffer_length as usize
+    }
+
+    /// Offset from the start of the CnC file to the counters values buffer
+    pub fn counter_values_buffer_offset(&self) -> usize {
+        self.counter_metadata_buffer_offset() + self.counter_metadata_buffer_length as usize
+    }
+
+    /// Offset from the start of the CnC file to the error log buffer
+    pub fn error_log_buffer_offset(&self) -> usize {
+        self.counter_values_buffer_offset() + self.counter_values_buffer_length as usize
+    }
 }
 
 /// Length of the metadata block in a CnC file. Note that it's not equivalent
@@ -75,6 +112,49 @@ pub const CNC_VERSION: i32 = crate::sematic_version_compose(0, 0, 16);
 /// Filename for the CnC file located in the Aeron directory
 pub const CNC_FILE: &str = "cnc.dat";
 
+/// Memory map the CnC file in an Aeron directory
+pub fn map_cnc_file(aeron_dir: &Path) -> Result<MappedBuffer> {
+    let cnc_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(aeron_dir.join(CNC_FILE))?;
+    let mmap = unsafe { MmapOptions::default().map_mut(&cnc_file) }?;
+    // SAFETY: The CnC file is shared with the media driver and other clients, and is
+    // only ever accessed through `AtomicBuffer` operations.
+    Ok(unsafe { MappedBuffer::new(mmap) })
+}
+
+/// Get the error log section of a mapped CnC file
+pub fn error_log_buffer(cnc: &MappedBuffer) -> Result<MappedBuffer> {
+    let metadata = cnc.overlay::<MetaDataDefinition>(0)?;
+    // SAFETY: See `map_cnc_file`
+    unsafe {
+        cnc.view(
+            metadata.error_log_buffer_offset() as IndexT,
+            metadata.error_log_buffer_length,
+        )
+    }
+}
+
+/// Create a reader over the counters buffers of a mapped CnC file
+pub fn counters_reader(cnc: &MappedBuffer) -> Result<CountersReader<MappedBuffer>> {
+    let metadata = cnc.overlay::<MetaDataDefinition>(0)?;
+    // SAFETY: See `map_cnc_file`
+    let counters_metadata = unsafe {
+        cnc.view(
+            metadata.counter_metadata_buffer_offset() as IndexT,
+            metadata.counter_metadata_buffer_length,
+        )
+    }?;
+    let counters_values = unsafe {
+        cnc.view(
+            metadata.counter_values_buffer_offset() as IndexT,
+            metadata.counter_values_buffer_length,
+        )
+    }?;
+    Ok(CountersReader::new(counters_metadata, counters_values))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::cnc_descriptor::{MetaDataDefinition, CNC_FILE, CNC_VERSION};
@@ -0,0 +1,40 @@
+//! Flyweight implementation for driver responses about timed out clients
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+
+/// Raw response indicating the media driver has timed out a client and released
+/// its resources
+#[repr(C, packed(4))]
+pub struct ClientTimeoutDefn {
+    pub(in crate::command) client_id: i64,
+}
+
+impl<A> Flyweight<A, ClientTimeoutDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the identifier of the client that timed out
+    pub fn client_id(&self) -> i64 {
+        self.get_struct().client_id
+    }
+
+    /// Set the identifier of the client that timed out
+    pub fn put_client_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().client_id = value;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::client_timeout::ClientTimeoutDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn client_timeout_size() {
+        assert_eq!(
+            size_of::<ClientTimeoutDefn>(),
+            size_of::<aeron_driver_sys::aeron_client_timeout_stct>()
+        )
+    }
+}
@@ -0,0 +1,171 @@
+//! Flyweight implementation for commands to add a counter
+use crate::command::correlated_message::CorrelatedMessageDefn;
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::util::{bit, IndexT, Result};
+use std::mem::size_of;
+
+/// Raw command to add a counter. Layout of the trailing data:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          Key Length                           |
+/// +---------------------------------------------------------------+
+/// |                         Key Buffer                           ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// |                         Label Length                          |
+/// +---------------------------------------------------------------+
+/// |                         Label (ASCII)                        ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+///
+/// The label length is aligned to 4 bytes following the key buffer.
+#[repr(C, packed(4))]
+pub struct CounterMessageDefn {
+    pub(in crate::command) correlated_message: CorrelatedMessageDefn,
+    pub(in crate::command) type_id: i32,
+}
+
+const KEY_LENGTH_OFFSET: IndexT = size_of::<CounterMessageDefn>() as IndexT;
+const KEY_BUFFER_OFFSET: IndexT = KEY_LENGTH_OFFSET + size_of::<i32>() as IndexT;
+
+impl<A> Flyweight<A, CounterMessageDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the client identifier of this request.
+    pub fn client_id(&self) -> i64 {
+        self.get_struct().correlated_message.client_id
+    }
+
+    /// Set the client identifier of this request.
+    pub fn put_client_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.client_id = value;
+        self
+    }
+
+    /// Retrieve the correlation identifier associated with this request. Used to
+    /// associate driver responses with a specific request.
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlated_message.correlation_id
+    }
+
+    /// Set the correlation identifier to be used with this request.
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.correlation_id = value;
+        self
+    }
+
+    /// Retrieve the type identifier of the counter to be added
+    pub fn type_id(&self) -> i32 {
+        self.get_struct().type_id
+    }
+
+    /// Set the type identifier of the counter to be added
+    pub fn put_type_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().type_id = value;
+        self
+    }
+
+    /// Retrieve the length of the key buffer
+    pub fn key_length(&self) -> Result<i32> {
+        self.buffer.get_i32(self.offset_of(KEY_LENGTH_OFFSET))
+    }
+
+    /// Retrieve the key buffer associated with the counter
+    pub fn key_buffer(&self) -> Result<&[u8]> {
+        let length = self.key_length()?;
+        let offset = self.offset_of(KEY_BUFFER_OFFSET);
+        self.buffer.bounds_check(offset, length)?;
+        Ok(&self.buffer[offset as usize..(offset + length) as usize])
+    }
+
+    /// Set the key buffer associated with the counter. Because the label follows the key,
+    /// the key must be written before the label.
+    pub fn put_key_buffer(&mut self, key: &[u8]) -> Result<&mut Self> {
+        let key_length = key.len() as i32;
+        let offset = self.offset_of(KEY_LENGTH_OFFSET);
+        self.buffer.put_i32(offset, key_length)?;
+        if key_length > 0 {
+            let offset = self.offset_of(KEY_BUFFER_OFFSET);
+            self.buffer.put_slice(offset, key, 0, key_length)?;
+        }
+        Ok(self)
+    }
+
+    /// Retrieve the length of the label
+    pub fn label_length(&self) -> Result<i32> {
+        let offset = self.offset_of(self.label_length_offset()?);
+        self.buffer.get_i32(offset)
+    }
+
+    /// Retrieve the label associated with the counter
+    pub fn label(&self) -> Result<&[u8]> {
+        let length = self.label_length()?;
+        let offset = self.offset_of(self.label_length_offset()? + size_of::<i32>() as IndexT);
+        self.buffer.bounds_check(offset, length)?;
+        Ok(&self.buffer[offset as usize..(offset + length) as usize])
+    }
+
+    /// Set the label associated with the counter
+    pub fn put_label(&mut self, label: &str) -> Result<&mut Self> {
+        let label_length = label.len() as i32;
+        let offset = self.offset_of(self.label_length_offset()?);
+        self.buffer.put_i32(offset, label_length)?;
+        if label_length > 0 {
+            let offset = offset + size_of::<i32>() as IndexT;
+            self.buffer
+                .put_slice(offset, label.as_bytes(), 0, label_length)?;
+        }
+        Ok(self)
+    }
+
+    /// Get the total byte length of this counter command
+    pub fn length(&self) -> Result<IndexT> {
+        Ok(self.label_length_offset()? + size_of::<i32>() as IndexT + self.label_length()?)
+    }
+
+    fn label_length_offset(&self) -> Result<IndexT> {
+        let key_length = self.key_length()?;
+        Ok(bit::align((KEY_BUFFER_OFFSET + key_length) as usize, size_of::<i32>()) as IndexT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::counter_message::CounterMessageDefn;
+    use crate::command::flyweight::Flyweight;
+    use std::mem::size_of;
+
+    #[test]
+    fn counter_command_size() {
+        assert_eq!(
+            size_of::<CounterMessageDefn>(),
+            size_of::<aeron_driver_sys::aeron_counter_command_stct>()
+        )
+    }
+
+    #[test]
+    fn counter_command_round_trip() {
+        let mut buffer = vec![0u8; 256];
+        let mut command = Flyweight::new::<CounterMessageDefn>(&mut buffer[..], 0).unwrap();
+        command
+            .put_client_id(1)
+            .put_correlation_id(2)
+            .put_type_id(1001);
+        command.put_key_buffer(&[1, 2, 3]).unwrap();
+        command.put_label("label").unwrap();
+
+        assert_eq!(command.client_id(), 1);
+        assert_eq!(command.correlation_id(), 2);
+        assert_eq!(command.type_id(), 1001);
+        assert_eq!(command.key_buffer().unwrap(), &[1, 2, 3]);
+        assert_eq!(command.label().unwrap(), b"label");
+        // 20 byte header, 4 byte key length, 3 byte key aligned to 4, 4 byte label length
+        assert_eq!(command.length().unwrap(), 20 + 4 + 4 + 4 + 5);
+    }
+}
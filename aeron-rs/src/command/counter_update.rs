@@ -0,0 +1,53 @@
+//! Flyweight implementation for driver responses about counter availability
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+
+/// Raw response indicating a counter is ready or no longer available. When the counter
+/// is ready, the correlation identifier matches the request that added it; when it's
+/// unavailable, the correlation identifier is the counter's registration identifier.
+#[repr(C, packed(4))]
+pub struct CounterUpdateDefn {
+    pub(in crate::command) correlation_id: i64,
+    pub(in crate::command) counter_id: i32,
+}
+
+impl<A> Flyweight<A, CounterUpdateDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier associated with this response
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier associated with this response
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+
+    /// Retrieve the identifier of the counter within the counters buffers
+    pub fn counter_id(&self) -> i32 {
+        self.get_struct().counter_id
+    }
+
+    /// Set the identifier of the counter within the counters buffers
+    pub fn put_counter_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().counter_id = value;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::counter_update::CounterUpdateDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn counter_update_size() {
+        assert_eq!(
+            size_of::<CounterUpdateDefn>(),
+            size_of::<aeron_driver_sys::aeron_counter_update_stct>()
+        )
+    }
+}
@@ -0,0 +1,134 @@
+//! Flyweight implementation for commands to add or remove a destination
+use crate::command::correlated_message::CorrelatedMessageDefn;
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::util::{IndexT, Result};
+use std::mem::size_of;
+
+/// Raw command to add or remove a destination of a multi-destination publication
+/// or subscription. Layout:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          Client ID                            |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                        Correlation ID                         |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                        Registration ID                        |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                        Channel Length                         |
+/// +---------------------------------------------------------------+
+/// |                           Channel                            ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+#[repr(C, packed(4))]
+pub struct DestinationMessageDefn {
+    pub(in crate::command) correlated_message: CorrelatedMessageDefn,
+    pub(in crate::command) registration_id: i64,
+    pub(in crate::command) channel_length: i32,
+}
+
+const CHANNEL_OFFSET: IndexT = size_of::<DestinationMessageDefn>() as IndexT;
+
+impl<A> Flyweight<A, DestinationMessageDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the client identifier of this request.
+    pub fn client_id(&self) -> i64 {
+        self.get_struct().correlated_message.client_id
+    }
+
+    /// Set the client identifier of this request.
+    pub fn put_client_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.client_id = value;
+        self
+    }
+
+    /// Retrieve the correlation identifier associated with this request. Used to
+    /// associate driver responses with a specific request.
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlated_message.correlation_id
+    }
+
+    /// Set the correlation identifier to be used with this request.
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.correlation_id = value;
+        self
+    }
+
+    /// Retrieve the registration identifier of the publication or subscription
+    /// the destination belongs to
+    pub fn registration_id(&self) -> i64 {
+        self.get_struct().registration_id
+    }
+
+    /// Set the registration identifier of the publication or subscription
+    /// the destination belongs to
+    pub fn put_registration_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().registration_id = value;
+        self
+    }
+
+    /// Retrieve the length of the destination channel in bytes
+    pub fn channel_length(&self) -> i32 {
+        self.get_struct().channel_length
+    }
+
+    /// Retrieve the channel of the destination
+    pub fn channel(&self) -> Result<&str> {
+        self.string_at(CHANNEL_OFFSET, self.channel_length())
+    }
+
+    /// Set the channel of the destination
+    pub fn put_channel(&mut self, value: &str) -> Result<&mut Self> {
+        self.put_string_at(CHANNEL_OFFSET, value)?;
+        self.get_struct_mut().channel_length = value.len() as i32;
+        Ok(self)
+    }
+
+    /// Get the total byte length of this command
+    pub fn length(&self) -> IndexT {
+        CHANNEL_OFFSET + self.channel_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::destination_message::DestinationMessageDefn;
+    use crate::command::flyweight::Flyweight;
+    use std::mem::size_of;
+
+    #[test]
+    fn destination_message_size() {
+        assert_eq!(
+            size_of::<DestinationMessageDefn>(),
+            size_of::<aeron_driver_sys::aeron_destination_command_stct>()
+        )
+    }
+
+    #[test]
+    fn destination_message_round_trip() {
+        let mut buffer = [0u8; 64];
+        Flyweight::new::<DestinationMessageDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_client_id(1)
+            .put_correlation_id(2)
+            .put_registration_id(3)
+            .put_channel("aeron:udp?endpoint=10.0.0.2:40123")
+            .unwrap();
+
+        let command = Flyweight::new::<DestinationMessageDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(command.client_id(), 1);
+        assert_eq!(command.correlation_id(), 2);
+        assert_eq!(command.registration_id(), 3);
+        assert_eq!(command.channel(), Ok("aeron:udp?endpoint=10.0.0.2:40123"));
+        assert_eq!(command.length(), 28 + 33);
+    }
+}
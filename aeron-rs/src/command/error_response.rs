@@ -0,0 +1,174 @@
+//! Flyweight implementation for error responses to client commands
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::control_protocol::ErrorCode;
+use crate::util::{IndexT, Result};
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem::size_of;
+
+/// Raw response indicating the media driver failed to process a command. Layout:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |              Offending Command Correlation ID                 |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                         Error Code                            |
+/// +---------------------------------------------------------------+
+/// |                   Error Message Length                        |
+/// +---------------------------------------------------------------+
+/// |                       Error Message                          ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+#[repr(C, packed(4))]
+pub struct ErrorResponseDefn {
+    pub(in crate::command) offending_command_correlation_id: i64,
+    pub(in crate::command) error_code: i32,
+    pub(in crate::command) error_message_length: i32,
+}
+
+const ERROR_MESSAGE_OFFSET: IndexT = size_of::<ErrorResponseDefn>() as IndexT;
+
+impl<A> Flyweight<A, ErrorResponseDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the command that failed
+    pub fn offending_command_correlation_id(&self) -> i64 {
+        self.get_struct().offending_command_correlation_id
+    }
+
+    /// Set the correlation identifier of the command that failed
+    pub fn put_offending_command_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().offending_command_correlation_id = value;
+        self
+    }
+
+    /// Retrieve the error code. See `ErrorCode` for known values.
+    pub fn error_code(&self) -> i32 {
+        self.get_struct().error_code
+    }
+
+    /// Set the error code
+    pub fn put_error_code(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().error_code = value;
+        self
+    }
+
+    /// Retrieve the length of the error message in bytes
+    pub fn error_message_length(&self) -> i32 {
+        self.get_struct().error_message_length
+    }
+
+    /// Retrieve the error message describing why the command failed
+    pub fn error_message(&self) -> Result<&[u8]> {
+        let length = self.error_message_length();
+        let offset = self.offset_of(ERROR_MESSAGE_OFFSET);
+        self.buffer.bounds_check(offset, length)?;
+        Ok(&self.buffer[offset as usize..(offset + length) as usize])
+    }
+
+    /// Set the error message describing why the command failed
+    pub fn put_error_message(&mut self, message: &str) -> Result<&mut Self> {
+        let length = message.len() as i32;
+        let offset = self.offset_of(ERROR_MESSAGE_OFFSET);
+        self.buffer
+            .put_slice(offset, message.as_bytes(), 0, length)?;
+        self.get_struct_mut().error_message_length = length;
+        Ok(self)
+    }
+
+    /// Get the total byte length of this error response
+    pub fn length(&self) -> IndexT {
+        ERROR_MESSAGE_OFFSET + self.error_message_length()
+    }
+
+    /// Copy this response into an owned `DriverErrorResponse`
+    pub fn to_response(&self) -> Result<DriverErrorResponse> {
+        Ok(DriverErrorResponse {
+            offending_command_correlation_id: self.offending_command_correlation_id(),
+            // Codes added by newer media drivers are reported as generic errors
+            error_code: ErrorCode::try_from(self.error_code() as u32)
+                .unwrap_or(ErrorCode::GenericError),
+            error_message: String::from_utf8_lossy(self.error_message()?).into_owned(),
+        })
+    }
+}
+
+/// Error sent by the media driver in response to a command from this client
+#[derive(Clone, Debug, PartialEq)]
+pub struct DriverErrorResponse {
+    /// Correlation identifier of the command that failed
+    pub offending_command_correlation_id: i64,
+    /// Category of the error
+    pub error_code: ErrorCode,
+    /// Description of the error from the media driver
+    pub error_message: String,
+}
+
+impl fmt::Display for DriverErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} for command {}: {}",
+            self.error_code, self.offending_command_correlation_id, self.error_message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::error_response::{DriverErrorResponse, ErrorResponseDefn};
+    use crate::command::flyweight::Flyweight;
+    use crate::control_protocol::ErrorCode;
+    use std::mem::size_of;
+
+    #[test]
+    fn error_response_size() {
+        assert_eq!(
+            size_of::<ErrorResponseDefn>(),
+            size_of::<aeron_driver_sys::aeron_error_response_stct>()
+        )
+    }
+
+    #[test]
+    fn error_response_round_trip() {
+        let mut buffer = [0u8; 64];
+        Flyweight::new::<ErrorResponseDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_offending_command_correlation_id(42)
+            .put_error_code(ErrorCode::InvalidChannel as i32)
+            .put_error_message("unknown media: tcp")
+            .unwrap();
+
+        let response = Flyweight::new::<ErrorResponseDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(response.length(), 16 + 18);
+        assert_eq!(
+            response.to_response(),
+            Ok(DriverErrorResponse {
+                offending_command_correlation_id: 42,
+                error_code: ErrorCode::InvalidChannel,
+                error_message: "unknown media: tcp".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_error_code_is_generic() {
+        let mut buffer = [0u8; 16];
+        Flyweight::new::<ErrorResponseDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_error_code(1000);
+
+        let response = Flyweight::new::<ErrorResponseDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(response.error_code(), 1000);
+        assert_eq!(
+            response.to_response().unwrap().error_code,
+            ErrorCode::GenericError
+        );
+    }
+}
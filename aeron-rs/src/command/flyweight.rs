@@ -1,7 +1,8 @@
 //! Flyweight pattern implementation for messages to and from the media driver.
 use crate::concurrent::AtomicBuffer;
-use crate::util::{IndexT, Result};
+use crate::util::{AeronError, IndexT, Result};
 use std::marker::PhantomData;
+use std::str;
 
 /// Flyweight holder object. Wrapper around an underlying `AtomicBuffer` and
 /// offset within that buffer that all future operations are relative to.
@@ -54,6 +55,12 @@ where
         self.buffer.overlay_mut::<S>(self.base_offset).unwrap()
     }
 
+    /// Translate an offset relative to the start of this message into an offset
+    /// within the underlying buffer.
+    pub(crate) fn offset_of(&self, offset: IndexT) -> IndexT {
+        self.base_offset + offset
+    }
+
     pub(crate) fn bytes_at(&self, offset: IndexT) -> &[u8] {
         let offset = (self.base_offset + offset) as usize;
         // FIXME: Unwrap is unjustified here.
@@ -63,4 +70,21 @@ where
         self.buffer.bounds_check(offset as IndexT, 0).unwrap();
         &self.buffer[offset..]
     }
+
+    /// Read a UTF-8 string of `length` bytes at an offset relative to the start
+    /// of this message
+    pub(crate) fn string_at(&self, offset: IndexT, length: i32) -> Result<&str> {
+        let offset = self.offset_of(offset);
+        self.buffer.bounds_check(offset, length)?;
+        str::from_utf8(&self.buffer[offset as usize..(offset + length) as usize])
+            .map_err(|_| AeronError::IllegalState("string is not valid UTF-8"))
+    }
+
+    /// Write a string at an offset relative to the start of this message. The length
+    /// of the string is not written.
+    pub(crate) fn put_string_at(&mut self, offset: IndexT, value: &str) -> Result<()> {
+        let offset = self.offset_of(offset);
+        self.buffer
+            .put_slice(offset, value.as_bytes(), 0, value.len() as IndexT)
+    }
 }
@@ -0,0 +1,205 @@
+//! Flyweight implementation for driver responses about new images
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::util::{bit, IndexT, Result};
+use std::mem::size_of;
+
+/// Raw response indicating the log buffers for an image are ready, and a subscription
+/// can begin consuming from it. Layout:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                        Correlation ID                         |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                          Session ID                           |
+/// +---------------------------------------------------------------+
+/// |                           Stream ID                           |
+/// +---------------------------------------------------------------+
+/// |                Subscriber Registration ID                     |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                   Subscriber Position ID                      |
+/// +---------------------------------------------------------------+
+/// |                        Log File Length                        |
+/// +---------------------------------------------------------------+
+/// |                         Log File Name                        ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// |                    Source Identity Length                     |
+/// +---------------------------------------------------------------+
+/// |                    Source Identity Name                      ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+#[repr(C, packed(4))]
+pub struct ImageBuffersReadyDefn {
+    pub(in crate::command) correlation_id: i64,
+    pub(in crate::command) session_id: i32,
+    pub(in crate::command) stream_id: i32,
+    pub(in crate::command) subscriber_registration_id: i64,
+    pub(in crate::command) subscriber_position_id: i32,
+}
+
+const LOG_FILE_LENGTH_OFFSET: IndexT = size_of::<ImageBuffersReadyDefn>() as IndexT;
+const LOG_FILE_NAME_OFFSET: IndexT = LOG_FILE_LENGTH_OFFSET + size_of::<i32>() as IndexT;
+
+impl<A> Flyweight<A, ImageBuffersReadyDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the image
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier of the image
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+
+    /// Retrieve the session identifier of the publication the image is from
+    pub fn session_id(&self) -> i32 {
+        self.get_struct().session_id
+    }
+
+    /// Set the session identifier of the publication the image is from
+    pub fn put_session_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().session_id = value;
+        self
+    }
+
+    /// Retrieve the stream identifier of the image
+    pub fn stream_id(&self) -> i32 {
+        self.get_struct().stream_id
+    }
+
+    /// Set the stream identifier of the image
+    pub fn put_stream_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().stream_id = value;
+        self
+    }
+
+    /// Retrieve the registration identifier of the subscription receiving the image
+    pub fn subscriber_registration_id(&self) -> i64 {
+        self.get_struct().subscriber_registration_id
+    }
+
+    /// Set the registration identifier of the subscription receiving the image
+    pub fn put_subscriber_registration_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().subscriber_registration_id = value;
+        self
+    }
+
+    /// Retrieve the identifier of the counter tracking the subscriber's position
+    pub fn subscriber_position_id(&self) -> i32 {
+        self.get_struct().subscriber_position_id
+    }
+
+    /// Set the identifier of the counter tracking the subscriber's position
+    pub fn put_subscriber_position_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().subscriber_position_id = value;
+        self
+    }
+
+    /// Retrieve the length of the log file name in bytes
+    pub fn log_file_length(&self) -> Result<i32> {
+        self.buffer.get_i32(self.offset_of(LOG_FILE_LENGTH_OFFSET))
+    }
+
+    /// Retrieve the name of the file holding the image's log buffers
+    pub fn log_file_name(&self) -> Result<&str> {
+        self.string_at(LOG_FILE_NAME_OFFSET, self.log_file_length()?)
+    }
+
+    /// Set the name of the file holding the image's log buffers. Must be set
+    /// before the source identity, as it determines where the source identity starts.
+    pub fn put_log_file_name(&mut self, value: &str) -> Result<&mut Self> {
+        let offset = self.offset_of(LOG_FILE_LENGTH_OFFSET);
+        self.buffer.put_i32(offset, value.len() as i32)?;
+        self.put_string_at(LOG_FILE_NAME_OFFSET, value)?;
+        Ok(self)
+    }
+
+    /// Retrieve the length of the source identity in bytes
+    pub fn source_identity_length(&self) -> Result<i32> {
+        let offset = self.offset_of(self.source_identity_length_offset()?);
+        self.buffer.get_i32(offset)
+    }
+
+    /// Retrieve the identity of the image's source, e.g. the address of the sender
+    pub fn source_identity(&self) -> Result<&str> {
+        let offset = self.source_identity_length_offset()? + size_of::<i32>() as IndexT;
+        self.string_at(offset, self.source_identity_length()?)
+    }
+
+    /// Set the identity of the image's source
+    pub fn put_source_identity(&mut self, value: &str) -> Result<&mut Self> {
+        let length_offset = self.source_identity_length_offset()?;
+        let offset = self.offset_of(length_offset);
+        self.buffer.put_i32(offset, value.len() as i32)?;
+        self.put_string_at(length_offset + size_of::<i32>() as IndexT, value)?;
+        Ok(self)
+    }
+
+    /// Get the total byte length of this response
+    pub fn length(&self) -> Result<IndexT> {
+        Ok(self.source_identity_length_offset()?
+            + size_of::<i32>() as IndexT
+            + self.source_identity_length()?)
+    }
+
+    fn source_identity_length_offset(&self) -> Result<IndexT> {
+        let log_file_length = self.log_file_length()?;
+        Ok(bit::align(
+            (LOG_FILE_NAME_OFFSET + log_file_length) as usize,
+            size_of::<i32>(),
+        ) as IndexT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::flyweight::Flyweight;
+    use crate::command::image_buffers_ready::ImageBuffersReadyDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn image_buffers_ready_size() {
+        assert_eq!(
+            size_of::<ImageBuffersReadyDefn>(),
+            size_of::<aeron_driver_sys::aeron_image_buffers_ready_stct>()
+        )
+    }
+
+    #[test]
+    fn image_buffers_ready_round_trip() {
+        let mut buffer = [0u8; 128];
+        Flyweight::new::<ImageBuffersReadyDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_correlation_id(1)
+            .put_session_id(2)
+            .put_stream_id(3)
+            .put_subscriber_registration_id(4)
+            .put_subscriber_position_id(5)
+            .put_log_file_name("images/1.logbuffer")
+            .unwrap()
+            .put_source_identity("127.0.0.1:40123")
+            .unwrap();
+
+        let response = Flyweight::new::<ImageBuffersReadyDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(response.correlation_id(), 1);
+        assert_eq!(response.session_id(), 2);
+        assert_eq!(response.stream_id(), 3);
+        assert_eq!(response.subscriber_registration_id(), 4);
+        assert_eq!(response.subscriber_position_id(), 5);
+        assert_eq!(response.log_file_name(), Ok("images/1.logbuffer"));
+        assert_eq!(response.source_identity(), Ok("127.0.0.1:40123"));
+
+        // Log file name ends at 50, so the source identity length is aligned to 52
+        assert_eq!(response.length(), Ok(52 + 4 + 15));
+    }
+}
@@ -0,0 +1,129 @@
+//! Flyweight implementation for driver responses about images going away
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::util::{IndexT, Result};
+use std::mem::size_of;
+
+/// Raw response indicating an image is no longer available to a subscription. Layout:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                        Correlation ID                         |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                Subscription Registration ID                   |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                           Stream ID                           |
+/// +---------------------------------------------------------------+
+/// |                        Channel Length                         |
+/// +---------------------------------------------------------------+
+/// |                           Channel                            ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+#[repr(C, packed(4))]
+pub struct ImageMessageDefn {
+    pub(in crate::command) correlation_id: i64,
+    pub(in crate::command) subscription_registration_id: i64,
+    pub(in crate::command) stream_id: i32,
+    pub(in crate::command) channel_length: i32,
+}
+
+const CHANNEL_OFFSET: IndexT = size_of::<ImageMessageDefn>() as IndexT;
+
+impl<A> Flyweight<A, ImageMessageDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the image
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier of the image
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+
+    /// Retrieve the registration identifier of the subscription the image belonged to
+    pub fn subscription_registration_id(&self) -> i64 {
+        self.get_struct().subscription_registration_id
+    }
+
+    /// Set the registration identifier of the subscription the image belonged to
+    pub fn put_subscription_registration_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().subscription_registration_id = value;
+        self
+    }
+
+    /// Retrieve the stream identifier of the image
+    pub fn stream_id(&self) -> i32 {
+        self.get_struct().stream_id
+    }
+
+    /// Set the stream identifier of the image
+    pub fn put_stream_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().stream_id = value;
+        self
+    }
+
+    /// Retrieve the length of the channel in bytes
+    pub fn channel_length(&self) -> i32 {
+        self.get_struct().channel_length
+    }
+
+    /// Retrieve the channel of the subscription the image belonged to
+    pub fn channel(&self) -> Result<&str> {
+        self.string_at(CHANNEL_OFFSET, self.channel_length())
+    }
+
+    /// Set the channel of the subscription the image belonged to
+    pub fn put_channel(&mut self, value: &str) -> Result<&mut Self> {
+        self.put_string_at(CHANNEL_OFFSET, value)?;
+        self.get_struct_mut().channel_length = value.len() as i32;
+        Ok(self)
+    }
+
+    /// Get the total byte length of this response
+    pub fn length(&self) -> IndexT {
+        CHANNEL_OFFSET + self.channel_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::flyweight::Flyweight;
+    use crate::command::image_message::ImageMessageDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn image_message_size() {
+        assert_eq!(
+            size_of::<ImageMessageDefn>(),
+            size_of::<aeron_driver_sys::aeron_image_message_stct>()
+        )
+    }
+
+    #[test]
+    fn image_message_round_trip() {
+        let mut buffer = [0u8; 64];
+        Flyweight::new::<ImageMessageDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_correlation_id(7)
+            .put_subscription_registration_id(8)
+            .put_stream_id(1001)
+            .put_channel("aeron:ipc")
+            .unwrap();
+
+        let response = Flyweight::new::<ImageMessageDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(response.correlation_id(), 7);
+        assert_eq!(response.subscription_registration_id(), 8);
+        assert_eq!(response.stream_id(), 1001);
+        assert_eq!(response.channel(), Ok("aeron:ipc"));
+        assert_eq!(response.length(), 24 + 9);
+    }
+}
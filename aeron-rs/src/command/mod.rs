@@ -1,4 +1,17 @@
 //! Message definitions for interactions with the Media Driver
+pub mod client_timeout;
 pub mod correlated_message;
+pub mod counter_message;
+pub mod counter_update;
+pub mod destination_message;
+pub mod error_response;
 pub mod flyweight;
+pub mod image_buffers_ready;
+pub mod image_message;
+pub mod operation_succeeded;
+pub mod publication_buffers_ready;
+pub mod publication_message;
+pub mod remove_message;
+pub mod subscription_message;
+pub mod subscription_ready;
 pub mod terminate_driver;
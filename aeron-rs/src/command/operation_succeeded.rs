@@ -0,0 +1,40 @@
+//! Flyweight implementation for driver responses confirming a command succeeded
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+
+/// Raw response indicating a command that doesn't create a resource, such as removing
+/// a counter or adding a destination, was processed by the media driver
+#[repr(C, packed(4))]
+pub struct OperationSucceededDefn {
+    pub(in crate::command) correlation_id: i64,
+}
+
+impl<A> Flyweight<A, OperationSucceededDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the command that succeeded
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier of the command that succeeded
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::operation_succeeded::OperationSucceededDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn operation_succeeded_size() {
+        assert_eq!(
+            size_of::<OperationSucceededDefn>(),
+            size_of::<aeron_driver_sys::aeron_operation_succeeded_stct>()
+        )
+    }
+}
@@ -0,0 +1,183 @@
+//! Flyweight implementation for driver responses about new publications
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+use crate::util::{IndexT, Result};
+use std::mem::size_of;
+
+/// Raw response indicating the log buffers for a publication are ready. Sent for both
+/// regular and exclusive publications. Layout:
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                        Correlation ID                         |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                        Registration ID                        |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                          Session ID                           |
+/// +---------------------------------------------------------------+
+/// |                           Stream ID                           |
+/// +---------------------------------------------------------------+
+/// |                  Position Limit Counter ID                    |
+/// +---------------------------------------------------------------+
+/// |                  Channel Status Indicator ID                  |
+/// +---------------------------------------------------------------+
+/// |                        Log File Length                        |
+/// +---------------------------------------------------------------+
+/// |                         Log File Name                        ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+#[repr(C, packed(4))]
+pub struct PublicationBuffersReadyDefn {
+    pub(in crate::command) correlation_id: i64,
+    pub(in crate::command) registration_id: i64,
+    pub(in crate::command) session_id: i32,
+    pub(in crate::command) stream_id: i32,
+    pub(in crate::command) position_limit_counter_id: i32,
+    pub(in crate::command) channel_status_indicator_id: i32,
+    pub(in crate::command) log_file_length: i32,
+}
+
+const LOG_FILE_NAME_OFFSET: IndexT = size_of::<PublicationBuffersReadyDefn>() as IndexT;
+
+impl<A> Flyweight<A, PublicationBuffersReadyDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the command that added the publication
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier of the command that added the publication
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+
+    /// Retrieve the registration identifier of the publication. Differs from the
+    /// correlation identifier when a publication on the same channel and stream
+    /// already existed.
+    pub fn registration_id(&self) -> i64 {
+        self.get_struct().registration_id
+    }
+
+    /// Set the registration identifier of the publication
+    pub fn put_registration_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().registration_id = value;
+        self
+    }
+
+    /// Retrieve the session identifier of the publication
+    pub fn session_id(&self) -> i32 {
+        self.get_struct().session_id
+    }
+
+    /// Set the session identifier of the publication
+    pub fn put_session_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().session_id = value;
+        self
+    }
+
+    /// Retrieve the stream identifier of the publication
+    pub fn stream_id(&self) -> i32 {
+        self.get_struct().stream_id
+    }
+
+    /// Set the stream identifier of the publication
+    pub fn put_stream_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().stream_id = value;
+        self
+    }
+
+    /// Retrieve the identifier of the counter limiting how far the publication may advance
+    pub fn position_limit_counter_id(&self) -> i32 {
+        self.get_struct().position_limit_counter_id
+    }
+
+    /// Set the identifier of the counter limiting how far the publication may advance
+    pub fn put_position_limit_counter_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().position_limit_counter_id = value;
+        self
+    }
+
+    /// Retrieve the identifier of the counter tracking the status of the send channel
+    pub fn channel_status_indicator_id(&self) -> i32 {
+        self.get_struct().channel_status_indicator_id
+    }
+
+    /// Set the identifier of the counter tracking the status of the send channel
+    pub fn put_channel_status_indicator_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().channel_status_indicator_id = value;
+        self
+    }
+
+    /// Retrieve the length of the log file name in bytes
+    pub fn log_file_length(&self) -> i32 {
+        self.get_struct().log_file_length
+    }
+
+    /// Retrieve the name of the file holding the publication's log buffers
+    pub fn log_file_name(&self) -> Result<&str> {
+        self.string_at(LOG_FILE_NAME_OFFSET, self.log_file_length())
+    }
+
+    /// Set the name of the file holding the publication's log buffers
+    pub fn put_log_file_name(&mut self, value: &str) -> Result<&mut Self> {
+        self.put_string_at(LOG_FILE_NAME_OFFSET, value)?;
+        self.get_struct_mut().log_file_length = value.len() as i32;
+        Ok(self)
+    }
+
+    /// Get the total byte length of this response
+    pub fn length(&self) -> IndexT {
+        LOG_FILE_NAME_OFFSET + self.log_file_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::flyweight::Flyweight;
+    use crate::command::publication_buffers_ready::PublicationBuffersReadyDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn publication_buffers_ready_size() {
+        assert_eq!(
+            size_of::<PublicationBuffersReadyDefn>(),
+            size_of::<aeron_driver_sys::aeron_publication_buffers_ready_stct>()
+        )
+    }
+
+    #[test]
+    fn publication_buffers_ready_round_trip() {
+        let mut buffer = [0u8; 128];
+        Flyweight::new::<PublicationBuffersReadyDefn>(&mut buffer[..], 0)
+            .unwrap()
+            .put_correlation_id(1)
+            .put_registration_id(2)
+            .put_session_id(3)
+            .put_stream_id(4)
+            .put_position_limit_counter_id(5)
+            .put_channel_status_indicator_id(6)
+            .put_log_file_name("/dev/shm/aeron/publications/2.logbuffer")
+            .unwrap();
+
+        let response = Flyweight::new::<PublicationBuffersReadyDefn>(&mut buffer[..], 0).unwrap();
+        assert_eq!(response.correlation_id(), 1);
+        assert_eq!(response.registration_id(), 2);
+        assert_eq!(response.session_id(), 3);
+        assert_eq!(response.stream_id(), 4);
+        assert_eq!(response.position_limit_counter_id(), 5);
+        assert_eq!(response.channel_status_indicator_id(), 6);
+        assert_eq!(
+            response.log_file_name(),
+            Ok("/dev/shm/aeron/publications/2.logbuffer")
+        );
+        assert_eq!(response.length(), 36 + 39);
+    }
+}
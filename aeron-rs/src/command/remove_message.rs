@@ -0,0 +1,64 @@
+//! Flyweight implementation for commands to remove a publication, subscription or counter
+use crate::command::correlated_message::CorrelatedMessageDefn;
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+
+/// Raw command to remove a resource previously added by the client
+#[repr(C, packed(4))]
+pub struct RemoveMessageDefn {
+    pub(in crate::command) correlated_message: CorrelatedMessageDefn,
+    pub(in crate::command) registration_id: i64,
+}
+
+impl<A> Flyweight<A, RemoveMessageDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the client identifier of this request.
+    pub fn client_id(&self) -> i64 {
+        self.get_struct().correlated_message.client_id
+    }
+
+    /// Set the client identifier of this request.
+    pub fn put_client_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.client_id = value;
+        self
+    }
+
+    /// Retrieve the correlation identifier associated with this request. Used to
+    /// associate driver responses with a specific request.
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlated_message.correlation_id
+    }
+
+    /// Set the correlation identifier to be used with this request.
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlated_message.correlation_id = value;
+        self
+    }
+
+    /// Retrieve the registration identifier of the resource to be removed
+    pub fn registration_id(&self) -> i64 {
+        self.get_struct().registration_id
+    }
+
+    /// Set the registration identifier of the resource to be removed
+    pub fn put_registration_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().registration_id = value;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::remove_message::RemoveMessageDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn remove_command_size() {
+        assert_eq!(
+            size_of::<RemoveMessageDefn>(),
+            size_of::<aeron_driver_sys::aeron_remove_command_stct>()
+        )
+    }
+}
@@ -0,0 +1,51 @@
+//! Flyweight implementation for driver responses about new subscriptions
+use crate::command::flyweight::Flyweight;
+use crate::concurrent::AtomicBuffer;
+
+/// Raw response indicating a subscription has been added by the media driver
+#[repr(C, packed(4))]
+pub struct SubscriptionReadyDefn {
+    pub(in crate::command) correlation_id: i64,
+    pub(in crate::command) channel_status_indicator_id: i32,
+}
+
+impl<A> Flyweight<A, SubscriptionReadyDefn>
+where
+    A: AtomicBuffer,
+{
+    /// Retrieve the correlation identifier of the command that added the subscription
+    pub fn correlation_id(&self) -> i64 {
+        self.get_struct().correlation_id
+    }
+
+    /// Set the correlation identifier of the command that added the subscription
+    pub fn put_correlation_id(&mut self, value: i64) -> &mut Self {
+        self.get_struct_mut().correlation_id = value;
+        self
+    }
+
+    /// Retrieve the identifier of the counter tracking the status of the receive channel
+    pub fn channel_status_indicator_id(&self) -> i32 {
+        self.get_struct().channel_status_indicator_id
+    }
+
+    /// Set the identifier of the counter tracking the status of the receive channel
+    pub fn put_channel_status_indicator_id(&mut self, value: i32) -> &mut Self {
+        self.get_struct_mut().channel_status_indicator_id = value;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::subscription_ready::SubscriptionReadyDefn;
+    use std::mem::size_of;
+
+    #[test]
+    fn subscription_ready_size() {
+        assert_eq!(
+            size_of::<SubscriptionReadyDefn>(),
+            size_of::<aeron_driver_sys::aeron_subscription_ready_stct>()
+        )
+    }
+}
@@ -0,0 +1,454 @@
+//! Agents encapsulate a duty cycle: a unit of work that is performed repeatedly, typically
+//! on a dedicated thread. Agents can be run on their own thread with an `AgentRunner`,
+//! driven manually from an existing loop with an `AgentInvoker`, or combined so several
+//! agents share a single thread with a `CompositeAgent`.
+use crate::concurrent::idle_strategy::IdleStrategy;
+use crate::util::{AeronError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+
+/// A unit of work to be performed repeatedly
+pub trait Agent {
+    /// Called once before the first duty cycle
+    fn on_start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Perform a single duty cycle. Returns the amount of work done; zero
+    /// indicates there was no work available, and the caller may choose to idle.
+    fn do_work(&mut self) -> Result<i32>;
+
+    /// Called once after the last duty cycle
+    fn on_close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Name of the role this agent plays. Used to name threads the agent runs on.
+    fn role_name(&self) -> String;
+}
+
+impl<T> Agent for Box<T>
+where
+    T: Agent + ?Sized,
+{
+    fn on_start(&mut self) -> Result<()> {
+        (**self).on_start()
+    }
+
+    fn do_work(&mut self) -> Result<i32> {
+        (**self).do_work()
+    }
+
+    fn on_close(&mut self) -> Result<()> {
+        (**self).on_close()
+    }
+
+    fn role_name(&self) -> String {
+        (**self).role_name()
+    }
+}
+
+/// Signal an agent running on an `AgentRunner` thread to stop. Handles can be cloned
+/// and shared with other threads.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    running: Arc<AtomicBool>,
+}
+
+impl ShutdownHandle {
+    /// Ask the agent to stop after its current duty cycle
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Check whether the agent is still running
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+}
+
+/// Configure an agent to be run on a dedicated thread
+pub struct AgentRunner<A, I> {
+    agent: A,
+    idle_strategy: I,
+    error_handler: Box<dyn FnMut(AeronError) + Send>,
+}
+
+impl<A, I> AgentRunner<A, I>
+where
+    A: Agent + Send + 'static,
+    I: IdleStrategy + Send + 'static,
+{
+    /// Create a runner for an agent, using an idle strategy when duty cycles find no work.
+    /// By default, errors are printed to standard error and the agent continues running.
+    pub fn new(agent: A, idle_strategy: I) -> Self {
+        AgentRunner {
+            agent,
+            idle_strategy,
+            error_handler: Box::new(|error| eprintln!("Agent error: {}", error)),
+        }
+    }
+
+    /// Set the handler called with any errors returned by the agent. The agent
+    /// continues running after errors in its duty cycle.
+    pub fn set_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(AeronError) + Send + 'static,
+    {
+        self.error_handler = Box::new(handler);
+        self
+    }
+
+    /// Start the agent on a new thread named after the agent's role
+    pub fn start(self) -> Result<AgentRunnerHandle> {
+        let AgentRunner {
+            mut agent,
+            mut idle_strategy,
+            mut error_handler,
+        } = self;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let shutdown_handle = ShutdownHandle {
+            running: running.clone(),
+        };
+
+        let thread = thread::Builder::new()
+            .name(agent.role_name())
+            .spawn(move || {
+                match agent.on_start() {
+                    Ok(()) => {
+                        while running.load(Ordering::Acquire) {
+                            match agent.do_work() {
+                                Ok(work_count) => idle_strategy.idle(work_count),
+                                Err(error) => {
+                                    error_handler(error);
+                                    idle_strategy.idle(0);
+                                }
+                            }
+                        }
+                    }
+                    Err(error) => error_handler(error),
+                }
+
+                if let Err(error) = agent.on_close() {
+                    error_handler(error);
+                }
+                running.store(false, Ordering::Release);
+            })?;
+
+        Ok(AgentRunnerHandle {
+            shutdown_handle,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Handle to an agent running on its own thread. The agent is stopped and
+/// the thread joined when the handle is closed or dropped.
+pub struct AgentRunnerHandle {
+    shutdown_handle: ShutdownHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AgentRunnerHandle {
+    /// Get a handle that can be used to stop the agent from other threads
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown_handle.clone()
+    }
+
+    /// Check whether the agent is still running
+    pub fn is_running(&self) -> bool {
+        self.shutdown_handle.is_running()
+    }
+
+    /// Stop the agent and wait for its thread to finish. Fails if the agent panicked.
+    pub fn close(&mut self) -> Result<()> {
+        self.shutdown_handle.shutdown();
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .map_err(|_| AeronError::IllegalState("agent thread panicked")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for AgentRunnerHandle {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Drive an agent manually from an existing loop, rather than giving it a thread
+pub struct AgentInvoker<A> {
+    agent: A,
+    is_started: bool,
+    is_closed: bool,
+}
+
+impl<A> AgentInvoker<A>
+where
+    A: Agent,
+{
+    /// Create an invoker for an agent
+    pub fn new(agent: A) -> Self {
+        AgentInvoker {
+            agent,
+            is_started: false,
+            is_closed: false,
+        }
+    }
+
+    /// Get the agent being invoked
+    pub fn agent(&self) -> &A {
+        &self.agent
+    }
+
+    /// Check whether the agent has been started
+    pub fn is_started(&self) -> bool {
+        self.is_started
+    }
+
+    /// Check whether the agent has been closed
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Start the agent if it hasn't been already
+    pub fn start(&mut self) -> Result<()> {
+        if self.is_started {
+            return Ok(());
+        }
+
+        self.is_started = true;
+        self.agent.on_start()
+    }
+
+    /// Perform a single duty cycle of the agent, starting it first if necessary.
+    /// Returns the amount of work done; closed agents do no work.
+    pub fn invoke(&mut self) -> Result<i32> {
+        if self.is_closed {
+            return Ok(0);
+        }
+
+        self.start()?;
+        self.agent.do_work()
+    }
+
+    /// Close the agent. Closing an agent that's already closed has no effect.
+    pub fn close(&mut self) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        self.is_closed = true;
+        self.agent.on_close()
+    }
+}
+
+/// Combine several agents so they can share a single thread. Agents are started,
+/// invoked and closed in the order they were added.
+pub struct CompositeAgent {
+    agents: Vec<Box<dyn Agent + Send>>,
+}
+
+impl CompositeAgent {
+    /// Create a new composite of agents
+    pub fn new(agents: Vec<Box<dyn Agent + Send>>) -> Self {
+        CompositeAgent { agents }
+    }
+}
+
+impl Agent for CompositeAgent {
+    fn on_start(&mut self) -> Result<()> {
+        for agent in self.agents.iter_mut() {
+            agent.on_start()?;
+        }
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32> {
+        let mut work_count = 0;
+        for agent in self.agents.iter_mut() {
+            work_count += agent.do_work()?;
+        }
+        Ok(work_count)
+    }
+
+    /// Close every agent, even if some fail. Returns the first error encountered.
+    fn on_close(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for agent in self.agents.iter_mut() {
+            let close_result = agent.on_close();
+            if result.is_ok() {
+                result = close_result;
+            }
+        }
+        result
+    }
+
+    fn role_name(&self) -> String {
+        let names: Vec<String> = self.agents.iter().map(|a| a.role_name()).collect();
+        format!("[{}]", names.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::agent::{Agent, AgentInvoker, AgentRunner, CompositeAgent};
+    use crate::concurrent::idle_strategy::YieldingIdleStrategy;
+    use crate::util::{AeronError, Result};
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Counts {
+        starts: AtomicI32,
+        cycles: AtomicI32,
+        closes: AtomicI32,
+    }
+
+    struct CountingAgent {
+        name: &'static str,
+        counts: Arc<Counts>,
+        fail_work: bool,
+    }
+
+    impl CountingAgent {
+        fn new(name: &'static str) -> (Self, Arc<Counts>) {
+            let counts = Arc::new(Counts::default());
+            let agent = CountingAgent {
+                name,
+                counts: counts.clone(),
+                fail_work: false,
+            };
+            (agent, counts)
+        }
+    }
+
+    impl Agent for CountingAgent {
+        fn on_start(&mut self) -> Result<()> {
+            self.counts.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn do_work(&mut self) -> Result<i32> {
+            self.counts.cycles.fetch_add(1, Ordering::SeqCst);
+            if self.fail_work {
+                Err(AeronError::IllegalState("failed test work"))
+            } else {
+                Ok(1)
+            }
+        }
+
+        fn on_close(&mut self) -> Result<()> {
+            self.counts.closes.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn role_name(&self) -> String {
+            self.name.to_string()
+        }
+    }
+
+    #[test]
+    fn invoker_should_start_agent_on_first_invoke() {
+        let (agent, counts) = CountingAgent::new("agent");
+        let mut invoker = AgentInvoker::new(agent);
+
+        assert!(!invoker.is_started());
+        assert_eq!(invoker.invoke(), Ok(1));
+        assert_eq!(invoker.invoke(), Ok(1));
+        assert!(invoker.is_started());
+        assert_eq!(counts.starts.load(Ordering::SeqCst), 1);
+        assert_eq!(counts.cycles.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn invoker_should_not_invoke_closed_agent() {
+        let (agent, counts) = CountingAgent::new("agent");
+        let mut invoker = AgentInvoker::new(agent);
+
+        invoker.invoke().unwrap();
+        invoker.close().unwrap();
+        invoker.close().unwrap();
+        assert_eq!(invoker.invoke(), Ok(0));
+        assert_eq!(counts.cycles.load(Ordering::SeqCst), 1);
+        assert_eq!(counts.closes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn runner_should_run_agent_until_closed() {
+        let (agent, counts) = CountingAgent::new("runner-test");
+        let mut handle = AgentRunner::new(agent, YieldingIdleStrategy)
+            .start()
+            .unwrap();
+
+        while counts.cycles.load(Ordering::SeqCst) < 10 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(handle.is_running());
+
+        handle.close().unwrap();
+        assert!(!handle.is_running());
+        assert_eq!(counts.starts.load(Ordering::SeqCst), 1);
+        assert_eq!(counts.closes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn runner_should_stop_from_shutdown_handle() {
+        let (agent, counts) = CountingAgent::new("runner-test");
+        let handle = AgentRunner::new(agent, YieldingIdleStrategy)
+            .start()
+            .unwrap();
+
+        handle.shutdown_handle().shutdown();
+        while counts.closes.load(Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(!handle.is_running());
+    }
+
+    #[test]
+    fn runner_should_report_errors_and_continue() {
+        let (mut agent, counts) = CountingAgent::new("runner-test");
+        agent.fail_work = true;
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let handler_errors = errors.clone();
+
+        let mut handle = AgentRunner::new(agent, YieldingIdleStrategy)
+            .set_error_handler(move |e| handler_errors.lock().unwrap().push(e))
+            .start()
+            .unwrap();
+        while counts.cycles.load(Ordering::SeqCst) < 2 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        handle.close().unwrap();
+
+        let errors = errors.lock().unwrap();
+        assert!(errors.len() >= 2);
+        assert_eq!(errors[0], AeronError::IllegalState("failed test work"));
+    }
+
+    #[test]
+    fn composite_should_invoke_all_agents() {
+        let (first, first_counts) = CountingAgent::new("first");
+        let (second, second_counts) = CountingAgent::new("second");
+        let mut composite = CompositeAgent::new(vec![Box::new(first), Box::new(second)]);
+
+        assert_eq!(composite.role_name(), "[first,second]");
+        composite.on_start().unwrap();
+        assert_eq!(composite.do_work(), Ok(2));
+        composite.on_close().unwrap();
+
+        for counts in &[first_counts, second_counts] {
+            assert_eq!(counts.starts.load(Ordering::SeqCst), 1);
+            assert_eq!(counts.cycles.load(Ordering::SeqCst), 1);
+            assert_eq!(counts.closes.load(Ordering::SeqCst), 1);
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Read messages that are broadcast from the media driver; this is the primary means
-//! of receiving data.
+//! of receiving data. Also provides the means of transmitting messages for
+//! one-to-many communication outside the media driver.
 use crate::concurrent::AtomicBuffer;
 use crate::util::bit::align;
 use crate::util::{AeronError, IndexT, Result};
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::ops::Deref;
+use std::sync::atomic::{fence, AtomicI64, Ordering};
 
 /// Description of the broadcast buffer schema
 pub mod buffer_descriptor {
@@ -29,7 +31,9 @@ pub mod buffer_descriptor {
         if is_power_of_two(capacity) {
             Ok(())
         } else {
-            Err(AeronError::IllegalArgument)
+            Err(AeronError::IllegalArgument(
+                "broadcast buffer capacity must be a power of two",
+            ))
         }
     }
 }
@@ -38,7 +42,7 @@ pub mod buffer_descriptor {
 // QUESTION: Isn't this the same as the ring buffer descriptor?
 // Why not consolidate them?
 pub mod record_descriptor {
-    use crate::util::IndexT;
+    use crate::util::{AeronError, IndexT, Result};
 
     /// Message type to indicate a record used only
     /// for padding the buffer
@@ -70,6 +74,140 @@ pub mod record_descriptor {
     pub fn msg_offset(record_offset: IndexT) -> IndexT {
         record_offset + HEADER_LENGTH
     }
+
+    pub(super) fn check_msg_type_id(msg_type_id: i32) -> Result<()> {
+        if msg_type_id < 1 {
+            Err(AeronError::IllegalArgument(
+                "message type id must be greater than zero",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Transmit messages into a broadcast buffer for any number of receivers. Receivers
+/// that fall behind by more than the buffer capacity will be lapped, and must
+/// recover by skipping ahead to the latest message.
+///
+/// Only a single transmitter may write into a broadcast buffer at any time.
+pub struct BroadcastTransmitter<A>
+where
+    A: AtomicBuffer,
+{
+    buffer: A,
+    capacity: IndexT,
+    max_msg_length: IndexT,
+    tail_intent_counter_index: IndexT,
+    tail_counter_index: IndexT,
+    latest_counter_index: IndexT,
+}
+
+impl<A> BroadcastTransmitter<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a new transmitter backed by `buffer`
+    pub fn new(buffer: A) -> Result<Self> {
+        let capacity = buffer.capacity() - buffer_descriptor::TRAILER_LENGTH;
+        buffer_descriptor::check_capacity(capacity)?;
+
+        Ok(BroadcastTransmitter {
+            buffer,
+            capacity,
+            max_msg_length: capacity / 8,
+            tail_intent_counter_index: capacity + buffer_descriptor::TAIL_INTENT_COUNTER_OFFSET,
+            tail_counter_index: capacity + buffer_descriptor::TAIL_COUNTER_OFFSET,
+            latest_counter_index: capacity + buffer_descriptor::LATEST_COUNTER_OFFSET,
+        })
+    }
+
+    /// Get the total capacity of this broadcast transmitter
+    pub fn capacity(&self) -> IndexT {
+        self.capacity
+    }
+
+    /// Get the largest message that can be transmitted through this buffer
+    pub fn max_msg_length(&self) -> IndexT {
+        self.max_msg_length
+    }
+
+    /// Transmit a message to any receivers of the buffer. If the message does not fit
+    /// before the end of the buffer, a padding record is inserted and the message is
+    /// written at the start of the buffer instead.
+    pub fn transmit(&mut self, msg_type_id: i32, msg: &[u8]) -> Result<()> {
+        record_descriptor::check_msg_type_id(msg_type_id)?;
+        let length = msg.len() as IndexT;
+        if length > self.max_msg_length {
+            return Err(AeronError::InsufficientCapacity {
+                required: length,
+                capacity: self.max_msg_length,
+            });
+        }
+
+        let mut current_tail = self.buffer.get_i64(self.tail_counter_index)?;
+        let mut record_offset = (current_tail as i32) & (self.capacity - 1);
+        let record_length = length + record_descriptor::HEADER_LENGTH;
+        let aligned_record_length = align(
+            record_length as usize,
+            record_descriptor::RECORD_ALIGNMENT as usize,
+        ) as IndexT;
+        let new_tail = current_tail + i64::from(aligned_record_length);
+
+        let to_end_of_buffer = self.capacity - record_offset;
+        if to_end_of_buffer < aligned_record_length {
+            self.signal_tail_intent(new_tail + i64::from(to_end_of_buffer))?;
+
+            self.buffer.put_i32(
+                record_descriptor::length_offset(record_offset),
+                to_end_of_buffer,
+            )?;
+            self.buffer.put_i32(
+                record_descriptor::type_offset(record_offset),
+                record_descriptor::PADDING_MSG_TYPE_ID,
+            )?;
+
+            current_tail += i64::from(to_end_of_buffer);
+            record_offset = 0;
+        } else {
+            self.signal_tail_intent(new_tail)?;
+        }
+
+        self.buffer.put_i32(
+            record_descriptor::length_offset(record_offset),
+            record_length,
+        )?;
+        self.buffer
+            .put_i32(record_descriptor::type_offset(record_offset), msg_type_id)?;
+        self.buffer
+            .put_slice(record_descriptor::msg_offset(record_offset), msg, 0, length)?;
+
+        self.buffer
+            .put_i64(self.latest_counter_index, current_tail)?;
+        self.buffer.put_i64_ordered(
+            self.tail_counter_index,
+            current_tail + i64::from(aligned_record_length),
+        )
+    }
+
+    fn signal_tail_intent(&mut self, new_tail: i64) -> Result<()> {
+        self.buffer
+            .put_i64_ordered(self.tail_intent_counter_index, new_tail)?;
+        // Receivers must observe the new tail intent before any of the record is overwritten
+        fence(Ordering::Release);
+        Ok(())
+    }
+}
+
+impl<A> Deref for BroadcastTransmitter<A>
+where
+    A: AtomicBuffer,
+{
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
 }
 
 /// Receive messages from a transmission stream. Works by polling `receive_next`
@@ -205,6 +343,14 @@ where
             .get_i32(record_descriptor::type_offset(self.record_offset))?)
     }
 
+    // Skip ahead to the latest record. Used when the current record was overwritten,
+    // since the position of the next record was computed from a length that may have
+    // been overwritten too.
+    fn resynchronize(&mut self) -> Result<()> {
+        self.next_record = self.buffer.get_i64_volatile(self.latest_counter_index)?;
+        Ok(())
+    }
+
     fn _validate(&self, cursor: i64) -> bool {
         // UNWRAP: Length checks performed during initialization
         (cursor + i64::from(self.capacity))
@@ -215,17 +361,27 @@ where
     }
 }
 
+/// Default length of the scratch buffer used to copy messages out of the broadcast buffer,
+/// and the default upper bound that scratch buffer may grow to.
+pub const DEFAULT_SCRATCH_LENGTH: usize = 4096;
+
 /// Broadcast receiver that copies messages to an internal buffer.
 ///
 /// The benefit of copying every message is that we keep a consistent view of the data
 /// even if we're lapped while reading. However, this may be overkill if you can
 /// guarantee the stream never outpaces you.
+///
+/// By default, being lapped by the transmitter is treated as an error. If a loss
+/// handler is set, the receiver will instead report the number of laps to the handler
+/// and resynchronize to the latest message in the buffer.
 pub struct CopyBroadcastReceiver<A>
 where
     A: AtomicBuffer,
 {
     receiver: BroadcastReceiver<A>,
     scratch: Vec<u8>,
+    max_scratch_length: usize,
+    loss_handler: Option<Box<dyn FnMut(i64) + Send>>,
 }
 
 impl<A> CopyBroadcastReceiver<A>
@@ -236,10 +392,35 @@ where
     pub fn new(receiver: BroadcastReceiver<A>) -> Self {
         CopyBroadcastReceiver {
             receiver,
-            scratch: Vec::with_capacity(4096),
+            scratch: vec![0u8; DEFAULT_SCRATCH_LENGTH],
+            max_scratch_length: DEFAULT_SCRATCH_LENGTH,
+            loss_handler: None,
         }
     }
 
+    /// Set the largest size the scratch buffer may grow to in order to copy a message.
+    /// Messages larger than this will cause `receive` to return `InsufficientCapacity`.
+    pub fn set_max_scratch_length(mut self, max_scratch_length: usize) -> Self {
+        self.max_scratch_length = max_scratch_length;
+        self
+    }
+
+    /// Tolerate being lapped by the transmitter. Rather than returning `IllegalState`,
+    /// the handler is given the number of times this receiver was lapped, and reception
+    /// continues from the latest message in the buffer.
+    pub fn set_loss_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(i64) + Send + 'static,
+    {
+        self.loss_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Get the number of times the transmitter has lapped this receiver.
+    pub fn lapped_count(&self) -> i64 {
+        self.receiver.lapped_count()
+    }
+
     /// Attempt to receive a single message from the broadcast buffer,
     /// and deliver it to the message handler if successful.
     /// Returns the number of messages received.
@@ -251,15 +432,44 @@ where
         let last_seen_lapped_count = self.receiver.lapped_count();
 
         if self.receiver.receive_next()? {
-            if last_seen_lapped_count != self.receiver.lapped_count() {
-                // The C++ API uses IllegalArgument here, but returns IllegalState
-                // with the same message later.
-                return Err(AeronError::IllegalState);
+            let laps = self.receiver.lapped_count() - last_seen_lapped_count;
+            if laps != 0 {
+                match self.loss_handler.as_mut() {
+                    // `receive_next` has already moved us up to the latest message
+                    Some(loss_handler) => loss_handler(laps),
+                    // The C++ API uses IllegalArgument here, but returns IllegalState
+                    // with the same message later.
+                    None => {
+                        return Err(AeronError::IllegalState(
+                            "receiver was lapped by the transmitter",
+                        ))
+                    }
+                }
             }
 
             let length = self.receiver.length()?;
-            if length > AtomicBuffer::capacity(&self.scratch) {
-                return Err(AeronError::IllegalState);
+            if length < 0 || length as usize > self.max_scratch_length {
+                // A record overwritten before we read its header can report any length;
+                // only a record that's still valid can be too large to copy.
+                if !self.receiver.validate() {
+                    if self.loss_handler.is_some() {
+                        self.receiver.resynchronize()?;
+                        return Ok(messages_received);
+                    }
+                    return Err(AeronError::IllegalState(
+                        "message was overwritten while being copied",
+                    ));
+                }
+                if length < 0 {
+                    return Err(AeronError::IllegalState("record length is negative"));
+                }
+                return Err(AeronError::InsufficientCapacity {
+                    required: length,
+                    capacity: self.max_scratch_length as IndexT,
+                });
+            }
+            if length as usize > self.scratch.len() {
+                self.scratch.resize(length as usize, 0);
             }
 
             let msg_type_id = self.receiver.msg_type_id()?;
@@ -267,7 +477,14 @@ where
                 .put_bytes(0, &self.receiver.buffer, self.receiver.offset(), length)?;
 
             if !self.receiver.validate() {
-                return Err(AeronError::IllegalState);
+                if self.loss_handler.is_some() {
+                    // The message was overwritten while we were copying it
+                    self.receiver.resynchronize()?;
+                    return Ok(messages_received);
+                }
+                return Err(AeronError::IllegalState(
+                    "message was overwritten while being copied",
+                ));
             }
             handler(msg_type_id, &self.scratch[0..length as usize]);
             messages_received += 1;
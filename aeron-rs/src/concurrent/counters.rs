@@ -0,0 +1,671 @@
+//! Read and allocate counters that are published in shared memory. Counters are the
+//! primary means of observing the behavior of the media driver.
+//!
+//! Counters are stored across two buffers. The values buffer holds the counter values,
+//! each padded out to avoid false sharing:
+//!
+//! ```text
+//!  0                   1                   2                   3
+//!  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                        Counter Value                          |
+//! |                                                               |
+//! +---------------------------------------------------------------+
+//! |                     120 bytes of padding                     ...
+//!...                                                              |
+//! +---------------------------------------------------------------+
+//! |                   Repeats to end of buffer                   ...
+//!...                                                              |
+//! +---------------------------------------------------------------+
+//! ```
+//!
+//! The metadata buffer holds a description of each counter at the same index:
+//!
+//! ```text
+//!  0                   1                   2                   3
+//!  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                        Record State                           |
+//! +---------------------------------------------------------------+
+//! |                          Type Id                              |
+//! +---------------------------------------------------------------+
+//! |                  Free-for-reuse Deadline                      |
+//! |                                                               |
+//! +---------------------------------------------------------------+
+//! |                      112 bytes for key                       ...
+//!...                                                              |
+//! +-+-------------------------------------------------------------+
+//! |R|                      Label Length                           |
+//! +-+-------------------------------------------------------------+
+//! |                     380 bytes of Label                       ...
+//!...                                                              |
+//! +---------------------------------------------------------------+
+//! |                   Repeats to end of buffer                   ...
+//!...                                                              |
+//! +---------------------------------------------------------------+
+//! ```
+use crate::concurrent::AtomicBuffer;
+use crate::util::bit::CACHE_LINE_LENGTH;
+use crate::util::time::epoch_millis;
+use crate::util::{AeronError, IndexT, Result};
+use std::mem::size_of;
+use std::ops::Deref;
+
+/// Record has never been used
+pub const RECORD_UNUSED: i32 = 0;
+
+/// Record currently allocated for use
+pub const RECORD_ALLOCATED: i32 = 1;
+
+/// Record was active and now has been reclaimed
+pub const RECORD_RECLAIMED: i32 = -1;
+
+/// Deadline to indicate counter is not free to be reused
+pub const NOT_FREE_TO_REUSE: i64 = ::std::i64::MAX;
+
+/// Identifier used to indicate the absence of a counter
+pub const NULL_COUNTER_ID: i32 = -1;
+
+/// Length of a counter in the values buffer
+pub const COUNTER_LENGTH: IndexT = (CACHE_LINE_LENGTH * 2) as IndexT;
+
+/// Length of a counter description in the metadata buffer
+pub const METADATA_LENGTH: IndexT = COUNTER_LENGTH * 4;
+
+/// Offset in the metadata record for the counter type identifier
+pub const TYPE_ID_OFFSET: IndexT = size_of::<i32>() as IndexT;
+
+/// Offset in the metadata record for the deadline at which the counter may be reused
+pub const FREE_FOR_REUSE_DEADLINE_OFFSET: IndexT = TYPE_ID_OFFSET + size_of::<i32>() as IndexT;
+
+/// Offset in the metadata record for the key describing the counter
+pub const KEY_OFFSET: IndexT = FREE_FOR_REUSE_DEADLINE_OFFSET + size_of::<i64>() as IndexT;
+
+/// Offset in the metadata record for the length-prefixed counter label
+pub const LABEL_OFFSET: IndexT = COUNTER_LENGTH;
+
+/// Maximum length of a counter key
+pub const MAX_KEY_LENGTH: IndexT = LABEL_OFFSET - KEY_OFFSET;
+
+/// Maximum length of a counter label
+pub const MAX_LABEL_LENGTH: IndexT = METADATA_LENGTH - LABEL_OFFSET - size_of::<i32>() as IndexT;
+
+/// Offset in the values buffer of the counter with a given identifier
+pub fn counter_offset(counter_id: i32) -> IndexT {
+    counter_id * COUNTER_LENGTH
+}
+
+/// Offset in the metadata buffer of the description for a counter with a given identifier
+pub fn metadata_offset(counter_id: i32) -> IndexT {
+    counter_id * METADATA_LENGTH
+}
+
+/// Read counter values and metadata out of a pair of buffers. Usually the buffers
+/// are sections of the CnC file, but any counters that follow the same layout can be read.
+pub struct CountersReader<A>
+where
+    A: AtomicBuffer,
+{
+    metadata_buffer: A,
+    values_buffer: A,
+    max_counter_id: i32,
+}
+
+impl<A> CountersReader<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a new reader over existing counters buffers.
+    pub fn new(metadata_buffer: A, values_buffer: A) -> Self {
+        let max_counter_id = values_buffer.capacity() / COUNTER_LENGTH - 1;
+        CountersReader {
+            metadata_buffer,
+            values_buffer,
+            max_counter_id,
+        }
+    }
+
+    /// Get the largest counter identifier that can be stored in these buffers
+    pub fn max_counter_id(&self) -> i32 {
+        self.max_counter_id
+    }
+
+    /// Get the buffer holding the counter metadata
+    pub fn metadata_buffer(&self) -> &A {
+        &self.metadata_buffer
+    }
+
+    /// Get the buffer holding the counter values
+    pub fn values_buffer(&self) -> &A {
+        &self.values_buffer
+    }
+
+    /// Iterate over all allocated counters. The handler is given the counter identifier,
+    /// type identifier, key and label for each counter.
+    pub fn for_each<F>(&self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(i32, i32, &[u8], &str) -> (),
+    {
+        let mut counter_id = 0;
+        let mut offset = 0;
+
+        while offset + METADATA_LENGTH <= self.metadata_buffer.capacity() {
+            let record_state = self.metadata_buffer.get_i32_volatile(offset)?;
+
+            if record_state == RECORD_ALLOCATED {
+                let type_id = self.metadata_buffer.get_i32(offset + TYPE_ID_OFFSET)?;
+                let label = self.label_at(offset)?;
+                handler(counter_id, type_id, self.key_at(offset), &label);
+            } else if record_state == RECORD_UNUSED {
+                break;
+            }
+
+            counter_id += 1;
+            offset += METADATA_LENGTH;
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over the values of all allocated counters. The handler is given the
+    /// counter value, identifier, and label.
+    pub fn for_each_value<F>(&self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(i64, i32, &str) -> (),
+    {
+        let values_buffer = &self.values_buffer;
+        self.for_each(|counter_id, _type_id, _key, label| {
+            // Skip metadata records that have no matching slot in the values buffer
+            if let Ok(value) = values_buffer.get_i64_volatile(counter_offset(counter_id)) {
+                handler(value, counter_id, label)
+            }
+        })
+    }
+
+    /// Get the current value of a counter
+    pub fn counter_value(&self, counter_id: i32) -> Result<i64> {
+        self.validate_counter_id(counter_id)?;
+        self.values_buffer
+            .get_i64_volatile(counter_offset(counter_id))
+    }
+
+    /// Get the record state of a counter; one of `RECORD_UNUSED`, `RECORD_ALLOCATED`,
+    /// or `RECORD_RECLAIMED`
+    pub fn counter_state(&self, counter_id: i32) -> Result<i32> {
+        self.validate_counter_id(counter_id)?;
+        self.metadata_buffer
+            .get_i32_volatile(metadata_offset(counter_id))
+    }
+
+    /// Get the type identifier of a counter
+    pub fn counter_type_id(&self, counter_id: i32) -> Result<i32> {
+        self.validate_counter_id(counter_id)?;
+        self.metadata_buffer
+            .get_i32(metadata_offset(counter_id) + TYPE_ID_OFFSET)
+    }
+
+    /// Get the deadline (in milliseconds since 1 Jan 1970, UTC) after which a freed
+    /// counter may be reused. Counters still in use will return `NOT_FREE_TO_REUSE`.
+    pub fn free_for_reuse_deadline(&self, counter_id: i32) -> Result<i64> {
+        self.validate_counter_id(counter_id)?;
+        self.metadata_buffer
+            .get_i64_volatile(metadata_offset(counter_id) + FREE_FOR_REUSE_DEADLINE_OFFSET)
+    }
+
+    /// Get the key bytes of a counter
+    pub fn counter_key(&self, counter_id: i32) -> Result<&[u8]> {
+        self.validate_counter_id(counter_id)?;
+        Ok(self.key_at(metadata_offset(counter_id)))
+    }
+
+    /// Get the label of a counter
+    pub fn counter_label(&self, counter_id: i32) -> Result<String> {
+        self.validate_counter_id(counter_id)?;
+        self.label_at(metadata_offset(counter_id))
+    }
+
+    fn validate_counter_id(&self, counter_id: i32) -> Result<()> {
+        if counter_id < 0 || counter_id > self.max_counter_id {
+            Err(AeronError::IllegalArgument("counter id is out of range"))
+        } else {
+            self.metadata_buffer
+                .bounds_check(metadata_offset(counter_id), METADATA_LENGTH)
+        }
+    }
+
+    fn key_at(&self, record_offset: IndexT) -> &[u8] {
+        let key_start = (record_offset + KEY_OFFSET) as usize;
+        &self.metadata_buffer[key_start..key_start + MAX_KEY_LENGTH as usize]
+    }
+
+    fn label_at(&self, record_offset: IndexT) -> Result<String> {
+        let label_length = self
+            .metadata_buffer
+            .get_i32_volatile(record_offset + LABEL_OFFSET)?;
+        if label_length < 0 {
+            return Err(AeronError::IllegalState("counter label length is corrupt"));
+        }
+        if label_length > MAX_LABEL_LENGTH {
+            return Err(AeronError::IllegalState("counter label length is corrupt"));
+        }
+
+        let label_start = (record_offset + LABEL_OFFSET) as usize + size_of::<i32>();
+        let label = &self.metadata_buffer[label_start..label_start + label_length as usize];
+        Ok(String::from_utf8_lossy(label).into_owned())
+    }
+}
+
+/// Allocate and free counters in a pair of counters buffers, following the same layout
+/// as the media driver. Counters can be read back through the `CountersReader` methods.
+pub struct CountersManager<A>
+where
+    A: AtomicBuffer,
+{
+    reader: CountersReader<A>,
+    free_list: Vec<i32>,
+    id_high_water_mark: i32,
+    free_to_reuse_timeout_ms: i64,
+    epoch_clock: Box<dyn Fn() -> i64 + Send>,
+}
+
+impl<A> CountersManager<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a new manager over counters buffers. Freed counters may be
+    /// reused immediately.
+    pub fn new(metadata_buffer: A, values_buffer: A) -> Self {
+        CountersManager {
+            reader: CountersReader::new(metadata_buffer, values_buffer),
+            free_list: Vec::new(),
+            id_high_water_mark: -1,
+            free_to_reuse_timeout_ms: 0,
+            epoch_clock: Box::new(epoch_millis),
+        }
+    }
+
+    /// Set the time (in milliseconds) that must pass after a counter is freed
+    /// before it can be allocated again. Gives readers an opportunity to notice
+    /// the counter was freed.
+    pub fn set_free_to_reuse_timeout_ms(mut self, timeout_ms: i64) -> Self {
+        self.free_to_reuse_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Set the clock used to determine when freed counters may be reused. The clock
+    /// must return milliseconds since 1 Jan 1970, UTC.
+    pub fn set_epoch_clock<F>(mut self, epoch_clock: F) -> Self
+    where
+        F: Fn() -> i64 + Send + 'static,
+    {
+        self.epoch_clock = Box::new(epoch_clock);
+        self
+    }
+
+    /// Allocate a new counter with a type identifier, key and label. Keys longer than
+    /// `MAX_KEY_LENGTH` are rejected, and labels longer than `MAX_LABEL_LENGTH` are truncated.
+    /// Returns the identifier of the allocated counter.
+    pub fn allocate(&mut self, type_id: i32, key: &[u8], label: &str) -> Result<i32> {
+        if key.len() > MAX_KEY_LENGTH as usize {
+            return Err(AeronError::IllegalArgument(
+                "counter key is longer than MAX_KEY_LENGTH",
+            ));
+        }
+
+        let counter_id = self.next_counter_id()?;
+        let record_offset = metadata_offset(counter_id);
+        let label_length = ::std::cmp::min(label.len(), MAX_LABEL_LENGTH as usize) as IndexT;
+
+        let result = (|| {
+            let metadata = &mut self.reader.metadata_buffer;
+            metadata.put_i32(record_offset + TYPE_ID_OFFSET, type_id)?;
+            metadata.put_i64(
+                record_offset + FREE_FOR_REUSE_DEADLINE_OFFSET,
+                NOT_FREE_TO_REUSE,
+            )?;
+            metadata.set_memory(record_offset + KEY_OFFSET, MAX_KEY_LENGTH as usize, 0)?;
+            metadata.put_slice(record_offset + KEY_OFFSET, key, 0, key.len() as IndexT)?;
+            metadata.put_slice(
+                record_offset + LABEL_OFFSET + size_of::<i32>() as IndexT,
+                label.as_bytes(),
+                0,
+                label_length,
+            )?;
+            metadata.put_i32(record_offset + LABEL_OFFSET, label_length)?;
+            metadata.put_i32_ordered(record_offset, RECORD_ALLOCATED)
+        })();
+
+        match result {
+            Ok(()) => Ok(counter_id),
+            Err(e) => {
+                self.free_list.push(counter_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Free a counter so that it may be reused once the reuse timeout has passed.
+    /// Fails with `AeronError::IllegalState` if the counter isn't currently allocated.
+    pub fn free(&mut self, counter_id: i32) -> Result<()> {
+        if self.reader.counter_state(counter_id)? != RECORD_ALLOCATED {
+            return Err(AeronError::IllegalState("counter is not allocated"));
+        }
+        let record_offset = metadata_offset(counter_id);
+        let deadline = (self.epoch_clock)() + self.free_to_reuse_timeout_ms;
+
+        let metadata = &mut self.reader.metadata_buffer;
+        metadata.put_i64(record_offset + FREE_FOR_REUSE_DEADLINE_OFFSET, deadline)?;
+        metadata.put_i32_ordered(record_offset, RECORD_RECLAIMED)?;
+        self.free_list.push(counter_id);
+
+        Ok(())
+    }
+
+    /// Set the value of a counter
+    pub fn set_counter_value(&mut self, counter_id: i32, value: i64) -> Result<()> {
+        self.reader.validate_counter_id(counter_id)?;
+        self.reader
+            .values_buffer
+            .put_i64_ordered(counter_offset(counter_id), value)
+    }
+
+    fn next_counter_id(&mut self) -> Result<i32> {
+        let now = (self.epoch_clock)();
+
+        for i in 0..self.free_list.len() {
+            let counter_id = self.free_list[i];
+            let deadline = self
+                .reader
+                .metadata_buffer
+                .get_i64_volatile(metadata_offset(counter_id) + FREE_FOR_REUSE_DEADLINE_OFFSET)?;
+
+            if now >= deadline {
+                self.free_list.remove(i);
+                self.reader
+                    .values_buffer
+                    .put_i64_ordered(counter_offset(counter_id), 0)?;
+                return Ok(counter_id);
+            }
+        }
+
+        let counter_id = self.id_high_water_mark + 1;
+        if counter_id > self.reader.max_counter_id
+            || self
+                .reader
+                .metadata_buffer
+                .bounds_check(metadata_offset(counter_id), METADATA_LENGTH)
+                .is_err()
+        {
+            return Err(AeronError::InsufficientCapacity {
+                required: counter_id + 1,
+                capacity: self.reader.max_counter_id + 1,
+            });
+        }
+
+        self.id_high_water_mark = counter_id;
+        Ok(counter_id)
+    }
+}
+
+impl<A> Deref for CountersManager<A>
+where
+    A: AtomicBuffer,
+{
+    type Target = CountersReader<A>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::counters::{
+        counter_offset, metadata_offset, CountersManager, CountersReader, COUNTER_LENGTH,
+        FREE_FOR_REUSE_DEADLINE_OFFSET, KEY_OFFSET, LABEL_OFFSET, MAX_KEY_LENGTH, MAX_LABEL_LENGTH,
+        METADATA_LENGTH, NOT_FREE_TO_REUSE, RECORD_ALLOCATED, RECORD_RECLAIMED, TYPE_ID_OFFSET,
+    };
+    use crate::concurrent::AtomicBuffer;
+    use crate::util::{AeronError, IndexT};
+    use memmap::MmapMut;
+    use std::mem::size_of;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+
+    const NUM_COUNTERS: usize = 4;
+
+    fn write_counter(
+        metadata: &mut Vec<u8>,
+        values: &mut Vec<u8>,
+        counter_id: i32,
+        type_id: i32,
+        label: &str,
+        value: i64,
+    ) {
+        let offset = metadata_offset(counter_id);
+        metadata.put_i32(offset + TYPE_ID_OFFSET, type_id).unwrap();
+        metadata
+            .put_i64(offset + FREE_FOR_REUSE_DEADLINE_OFFSET, NOT_FREE_TO_REUSE)
+            .unwrap();
+        metadata
+            .put_i32(offset + KEY_OFFSET, counter_id * 10)
+            .unwrap();
+        metadata
+            .put_i32(offset + LABEL_OFFSET, label.len() as i32)
+            .unwrap();
+        metadata
+            .put_slice(
+                offset + LABEL_OFFSET + size_of::<i32>() as IndexT,
+                label.as_bytes(),
+                0,
+                label.len() as IndexT,
+            )
+            .unwrap();
+        metadata.put_i32_ordered(offset, RECORD_ALLOCATED).unwrap();
+        values.put_i64(counter_offset(counter_id), value).unwrap();
+    }
+
+    fn buffers() -> (Vec<u8>, Vec<u8>) {
+        (
+            vec![0u8; METADATA_LENGTH as usize * NUM_COUNTERS],
+            vec![0u8; COUNTER_LENGTH as usize * NUM_COUNTERS],
+        )
+    }
+
+    #[test]
+    fn should_read_counter_metadata_and_value() {
+        let (mut metadata, mut values) = buffers();
+        write_counter(&mut metadata, &mut values, 0, 44, "my counter", 17);
+
+        let reader = CountersReader::new(metadata, values);
+        assert_eq!(reader.max_counter_id(), NUM_COUNTERS as i32 - 1);
+        assert_eq!(reader.counter_state(0), Ok(RECORD_ALLOCATED));
+        assert_eq!(reader.counter_type_id(0), Ok(44));
+        assert_eq!(reader.counter_value(0), Ok(17));
+        assert_eq!(reader.counter_label(0), Ok("my counter".to_string()));
+        assert_eq!(reader.counter_key(0).map(|k| k[0]), Ok(0));
+        assert_eq!(reader.free_for_reuse_deadline(0), Ok(NOT_FREE_TO_REUSE));
+    }
+
+    #[test]
+    fn should_reject_invalid_counter_id() {
+        let (metadata, values) = buffers();
+        let reader = CountersReader::new(metadata, values);
+
+        assert_eq!(
+            reader.counter_value(-1),
+            Err(AeronError::IllegalArgument("counter id is out of range"))
+        );
+        assert_eq!(
+            reader.counter_value(NUM_COUNTERS as i32),
+            Err(AeronError::IllegalArgument("counter id is out of range"))
+        );
+    }
+
+    #[test]
+    fn should_iterate_allocated_counters_only() {
+        let (mut metadata, mut values) = buffers();
+        write_counter(&mut metadata, &mut values, 0, 1, "first", 1);
+        write_counter(&mut metadata, &mut values, 1, 2, "second", 2);
+        write_counter(&mut metadata, &mut values, 2, 3, "third", 3);
+        metadata
+            .put_i32(metadata_offset(1), RECORD_RECLAIMED)
+            .unwrap();
+
+        let reader = CountersReader::new(metadata, values);
+
+        let mut seen = Vec::new();
+        reader
+            .for_each(|counter_id, type_id, key, label| {
+                seen.push((counter_id, type_id, key[0], label.to_string()))
+            })
+            .unwrap();
+        assert_eq!(
+            seen,
+            vec![
+                (0, 1, 0, "first".to_string()),
+                (2, 3, 20, "third".to_string())
+            ]
+        );
+
+        let mut total = 0;
+        reader.for_each_value(|value, _, _| total += value).unwrap();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn should_allocate_counters_readable_by_reader() {
+        let (metadata, values) = buffers();
+        let mut manager = CountersManager::new(metadata, values);
+
+        let first = manager.allocate(7, &[1, 2, 3, 4], "first").unwrap();
+        let second = manager.allocate(8, &[], "second").unwrap();
+        manager.set_counter_value(second, 99).unwrap();
+
+        assert_eq!(first, 0);
+        assert_eq!(second, 1);
+        assert_eq!(manager.counter_type_id(first), Ok(7));
+        assert_eq!(
+            manager.counter_key(first).map(|k| &k[..5]),
+            Ok(&[1, 2, 3, 4, 0][..])
+        );
+        assert_eq!(manager.counter_label(second), Ok("second".to_string()));
+        assert_eq!(manager.counter_value(second), Ok(99));
+        assert_eq!(
+            manager.free_for_reuse_deadline(first),
+            Ok(NOT_FREE_TO_REUSE)
+        );
+    }
+
+    #[test]
+    fn should_fail_when_buffers_are_full() {
+        let (metadata, values) = buffers();
+        let mut manager = CountersManager::new(metadata, values);
+
+        for _ in 0..NUM_COUNTERS {
+            manager.allocate(1, &[], "counter").unwrap();
+        }
+        assert_eq!(
+            manager.allocate(1, &[], "counter"),
+            Err(AeronError::InsufficientCapacity {
+                required: NUM_COUNTERS as i32 + 1,
+                capacity: NUM_COUNTERS as i32
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_oversized_key_and_truncate_label() {
+        let (metadata, values) = buffers();
+        let mut manager = CountersManager::new(metadata, values);
+
+        let key = vec![0u8; MAX_KEY_LENGTH as usize + 1];
+        assert_eq!(
+            manager.allocate(1, &key, "counter"),
+            Err(AeronError::IllegalArgument(
+                "counter key is longer than MAX_KEY_LENGTH"
+            ))
+        );
+
+        let label = "x".repeat(MAX_LABEL_LENGTH as usize + 10);
+        let counter_id = manager.allocate(1, &[], &label).unwrap();
+        assert_eq!(
+            manager.counter_label(counter_id).map(|l| l.len()),
+            Ok(MAX_LABEL_LENGTH as usize)
+        );
+    }
+
+    #[test]
+    fn should_reuse_counter_after_free_to_reuse_deadline() {
+        let now = Arc::new(AtomicI64::new(1000));
+        let clock = now.clone();
+
+        let (metadata, values) = buffers();
+        let mut manager = CountersManager::new(metadata, values)
+            .set_free_to_reuse_timeout_ms(100)
+            .set_epoch_clock(move || clock.load(Ordering::SeqCst));
+
+        let first = manager.allocate(1, &[], "first").unwrap();
+        manager.set_counter_value(first, 12).unwrap();
+        manager.free(first).unwrap();
+
+        assert_eq!(manager.counter_state(first), Ok(RECORD_RECLAIMED));
+        assert_eq!(manager.free_for_reuse_deadline(first), Ok(1100));
+
+        // Deadline not yet reached, so a fresh counter is allocated instead
+        assert_eq!(manager.allocate(1, &[], "second"), Ok(1));
+
+        now.store(1100, Ordering::SeqCst);
+        assert_eq!(manager.allocate(1, &[], "third"), Ok(first));
+        assert_eq!(manager.counter_value(first), Ok(0));
+        assert_eq!(manager.counter_label(first), Ok("third".to_string()));
+    }
+
+    #[test]
+    fn should_reject_free_of_unallocated_counter() {
+        let now = Arc::new(AtomicI64::new(1000));
+        let clock = now.clone();
+
+        let (metadata, values) = buffers();
+        let mut manager = CountersManager::new(metadata, values)
+            .set_free_to_reuse_timeout_ms(100)
+            .set_epoch_clock(move || clock.load(Ordering::SeqCst));
+
+        let never_allocated = 3;
+        assert_eq!(
+            manager.free(never_allocated),
+            Err(AeronError::IllegalState("counter is not allocated"))
+        );
+
+        let first = manager.allocate(1, &[], "first").unwrap();
+        manager.free(first).unwrap();
+        assert_eq!(
+            manager.free(first),
+            Err(AeronError::IllegalState("counter is not allocated"))
+        );
+        assert_eq!(manager.free_for_reuse_deadline(first), Ok(1100));
+
+        // The counter was only queued for reuse once, so it can't be handed out twice
+        now.store(1100, Ordering::SeqCst);
+        assert_eq!(manager.allocate(1, &[], "second"), Ok(first));
+        assert_eq!(manager.allocate(1, &[], "third"), Ok(1));
+    }
+
+    #[test]
+    fn should_allocate_in_memory_mapped_buffers() {
+        let metadata = MmapMut::map_anon(METADATA_LENGTH as usize * NUM_COUNTERS).unwrap();
+        let values = MmapMut::map_anon(COUNTER_LENGTH as usize * NUM_COUNTERS).unwrap();
+        let mut manager = CountersManager::new(metadata, values);
+
+        let counter_id = manager.allocate(3, &[], "mapped").unwrap();
+        manager.set_counter_value(counter_id, 5).unwrap();
+
+        let mut seen = Vec::new();
+        manager
+            .for_each_value(|value, counter_id, label| {
+                seen.push((value, counter_id, label.to_string()))
+            })
+            .unwrap();
+        assert_eq!(seen, vec![(5, counter_id, "mapped".to_string())]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Distinct error logs in shared memory. The media driver records each distinct error it
+//! encounters once, along with how many times and when it has been observed; this is the
+//! same format used by the "Error Log" section of the CnC file.
+use crate::concurrent::AtomicBuffer;
+use crate::util::bit::align;
+use crate::util::time::epoch_millis;
+use crate::util::{AeronError, IndexT, Result};
+
+/// Description of the error log record schema
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            Length                             |
+/// +---------------------------------------------------------------+
+/// |                     Observation Count                         |
+/// +---------------------------------------------------------------+
+/// |                Last Observation Timestamp                     |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |               First Observation Timestamp                     |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                     UTF-8 Encoded Error                      ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+pub mod error_log_descriptor {
+    use crate::util::IndexT;
+    use std::mem::size_of;
+
+    /// Offset from the beginning of a record to its length
+    pub const LENGTH_OFFSET: IndexT = 0;
+
+    /// Offset from the beginning of a record to the number of times the error was observed
+    pub const OBSERVATION_COUNT_OFFSET: IndexT = LENGTH_OFFSET + size_of::<i32>() as IndexT;
+
+    /// Offset from the beginning of a record to the time the error was last observed
+    pub const LAST_OBSERVATION_TIMESTAMP_OFFSET: IndexT =
+        OBSERVATION_COUNT_OFFSET + size_of::<i32>() as IndexT;
+
+    /// Offset from the beginning of a record to the time the error was first observed
+    pub const FIRST_OBSERVATION_TIMESTAMP_OFFSET: IndexT =
+        LAST_OBSERVATION_TIMESTAMP_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Offset from the beginning of a record to the encoded error
+    pub const ENCODED_ERROR_OFFSET: IndexT =
+        FIRST_OBSERVATION_TIMESTAMP_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Total header length for each record
+    pub const HEADER_LENGTH: IndexT = ENCODED_ERROR_OFFSET;
+
+    /// Alignment for all error log records
+    pub const RECORD_ALIGNMENT: IndexT = size_of::<i64>() as IndexT;
+}
+
+/// Read the distinct errors recorded in an error log
+pub mod error_log_reader {
+    use super::error_log_descriptor::*;
+    use super::*;
+
+    /// Read all errors last observed at or after `since_timestamp` (milliseconds since
+    /// 1 Jan 1970, UTC), in the order they were first recorded. The handler is given the
+    /// observation count, first observation timestamp, last observation timestamp, and
+    /// the error message. Returns the number of errors read.
+    pub fn read<A, F>(buffer: &A, mut handler: F, since_timestamp: i64) -> Result<i32>
+    where
+        A: AtomicBuffer,
+        F: FnMut(i32, i64, i64, &str) -> (),
+    {
+        let capacity = buffer.capacity();
+        let mut entries = 0;
+        let mut offset = 0;
+
+        while offset + HEADER_LENGTH <= capacity {
+            let length = buffer.get_i32_volatile(offset + LENGTH_OFFSET)?;
+            if length == 0 {
+                break;
+            }
+            if length < HEADER_LENGTH || offset + length > capacity {
+                return Err(AeronError::IllegalState(
+                    "error log record length is corrupt",
+                ));
+            }
+
+            let last_observation_timestamp =
+                buffer.get_i64_volatile(offset + LAST_OBSERVATION_TIMESTAMP_OFFSET)?;
+            if last_observation_timestamp >= since_timestamp {
+                entries += 1;
+
+                let observation_count =
+                    buffer.get_i32_volatile(offset + OBSERVATION_COUNT_OFFSET)?;
+                let first_observation_timestamp =
+                    buffer.get_i64(offset + FIRST_OBSERVATION_TIMESTAMP_OFFSET)?;
+                let error_start = (offset + ENCODED_ERROR_OFFSET) as usize;
+                let error_end = (offset + length) as usize;
+                let error = String::from_utf8_lossy(&buffer[error_start..error_end]);
+
+                handler(
+                    observation_count,
+                    first_observation_timestamp,
+                    last_observation_timestamp,
+                    &error,
+                );
+            }
+
+            offset += align(length as usize, RECORD_ALIGNMENT as usize) as IndexT;
+        }
+
+        Ok(entries)
+    }
+}
+
+struct DistinctObservation {
+    error: String,
+    offset: IndexT,
+}
+
+/// Record distinct errors into a buffer using the same layout as the media driver's
+/// error log, so they can be inspected with `error_log_reader`. An error that has been
+/// seen before increments its observation count and updates its last observation time
+/// rather than being recorded again.
+///
+/// Only a single writer may record into an error log at any time.
+pub struct DistinctErrorLog<A>
+where
+    A: AtomicBuffer,
+{
+    buffer: A,
+    observations: Vec<DistinctObservation>,
+    next_offset: IndexT,
+    epoch_clock: Box<dyn Fn() -> i64 + Send>,
+}
+
+impl<A> DistinctErrorLog<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a new error log writing into an empty buffer
+    pub fn new(buffer: A) -> Self {
+        DistinctErrorLog {
+            buffer,
+            observations: Vec::new(),
+            next_offset: 0,
+            epoch_clock: Box::new(epoch_millis),
+        }
+    }
+
+    /// Set the clock used to timestamp observations. The clock must return
+    /// milliseconds since 1 Jan 1970, UTC.
+    pub fn set_epoch_clock<F>(mut self, epoch_clock: F) -> Self
+    where
+        F: Fn() -> i64 + Send + 'static,
+    {
+        self.epoch_clock = Box::new(epoch_clock);
+        self
+    }
+
+    /// Get the buffer errors are recorded into
+    pub fn buffer(&self) -> &A {
+        &self.buffer
+    }
+
+    /// Record an observation of an error. Returns `Ok(false)` if the error has not been
+    /// seen before and there is no space left in the buffer to record it.
+    pub fn record(&mut self, error: &str) -> Result<bool> {
+        use error_log_descriptor::*;
+
+        let timestamp = (self.epoch_clock)();
+        let offset = match self.observations.iter().find(|o| o.error == error) {
+            Some(observation) => observation.offset,
+            None => {
+                let length = HEADER_LENGTH + error.len() as IndexT;
+                let offset = self.next_offset;
+                if offset + length > self.buffer.capacity() {
+                    return Ok(false);
+                }
+
+                self.buffer.put_slice(
+                    offset + ENCODED_ERROR_OFFSET,
+                    error.as_bytes(),
+                    0,
+                    error.len() as IndexT,
+                )?;
+                self.buffer
+                    .put_i64(offset + FIRST_OBSERVATION_TIMESTAMP_OFFSET, timestamp)?;
+                self.buffer
+                    .put_i32_ordered(offset + LENGTH_OFFSET, length)?;
+
+                self.next_offset =
+                    align((offset + length) as usize, RECORD_ALIGNMENT as usize) as IndexT;
+                self.observations.push(DistinctObservation {
+                    error: error.to_string(),
+                    offset,
+                });
+                offset
+            }
+        };
+
+        self.buffer
+            .get_and_add_i32(offset + OBSERVATION_COUNT_OFFSET, 1)?;
+        self.buffer
+            .put_i64_ordered(offset + LAST_OBSERVATION_TIMESTAMP_OFFSET, timestamp)?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,280 @@
+//! Strategies for what a thread should do when a duty cycle finds no work. Choosing a
+//! strategy trades latency against CPU usage: spinning reacts fastest but occupies a core,
+//! while sleeping frees the core at the cost of waking up late.
+use std::thread;
+use std::time::Duration;
+
+/// Decide what to do between duty cycles based on how much work was done
+pub trait IdleStrategy {
+    /// Perform idle behavior if no work was done in the last duty cycle.
+    /// Strategies that escalate their behavior will reset when work is done.
+    fn idle(&mut self, work_count: i32);
+
+    /// Reset the internal state of the strategy, if any
+    fn reset(&mut self);
+}
+
+impl<T> IdleStrategy for Box<T>
+where
+    T: IdleStrategy + ?Sized,
+{
+    fn idle(&mut self, work_count: i32) {
+        (**self).idle(work_count)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
+/// Spin without pausing the thread. Lowest latency, but consumes an entire core.
+#[derive(Clone, Debug, Default)]
+pub struct BusySpinIdleStrategy;
+
+impl IdleStrategy for BusySpinIdleStrategy {
+    #[allow(deprecated)]
+    fn idle(&mut self, work_count: i32) {
+        if work_count > 0 {
+            return;
+        }
+        // `std::hint::spin_loop` would be preferred, but isn't available on our minimum Rust version
+        ::std::sync::atomic::spin_loop_hint();
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Yield the thread to the scheduler when there is no work
+#[derive(Clone, Debug, Default)]
+pub struct YieldingIdleStrategy;
+
+impl IdleStrategy for YieldingIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count > 0 {
+            return;
+        }
+        thread::yield_now();
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Sleep for a fixed period when there is no work
+#[derive(Clone, Debug)]
+pub struct SleepingIdleStrategy {
+    sleep_period: Duration,
+}
+
+impl SleepingIdleStrategy {
+    /// Create a new strategy that sleeps for `sleep_period` when idle
+    pub fn new(sleep_period: Duration) -> Self {
+        SleepingIdleStrategy { sleep_period }
+    }
+}
+
+impl IdleStrategy for SleepingIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count > 0 {
+            return;
+        }
+        thread::sleep(self.sleep_period);
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Do nothing when idle; for callers that manage idling themselves
+#[derive(Clone, Debug, Default)]
+pub struct NoOpIdleStrategy;
+
+impl IdleStrategy for NoOpIdleStrategy {
+    fn idle(&mut self, _work_count: i32) {}
+
+    fn reset(&mut self) {}
+}
+
+/// Default number of spins before `BackoffIdleStrategy` starts yielding
+pub const DEFAULT_MAX_SPINS: u64 = 10;
+
+/// Default number of yields before `BackoffIdleStrategy` starts parking
+pub const DEFAULT_MAX_YIELDS: u64 = 5;
+
+/// Default shortest park period for `BackoffIdleStrategy`
+pub const DEFAULT_MIN_PARK_PERIOD: Duration = Duration::from_micros(1);
+
+/// Default longest park period for `BackoffIdleStrategy`
+pub const DEFAULT_MAX_PARK_PERIOD: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BackoffState {
+    NotIdle,
+    Spinning,
+    Yielding,
+    Parking,
+}
+
+/// Progressively back off when there is no work: first spin, then yield, then park
+/// for exponentially increasing periods up to a maximum. Any work resets the strategy.
+#[derive(Clone, Debug)]
+pub struct BackoffIdleStrategy {
+    max_spins: u64,
+    max_yields: u64,
+    min_park_period: Duration,
+    max_park_period: Duration,
+    state: BackoffState,
+    spins: u64,
+    yields: u64,
+    park_period: Duration,
+}
+
+impl BackoffIdleStrategy {
+    /// Create a new strategy that spins `max_spins` times, then yields `max_yields` times,
+    /// and then parks for periods doubling from `min_park_period` up to `max_park_period`.
+    pub fn new(
+        max_spins: u64,
+        max_yields: u64,
+        min_park_period: Duration,
+        max_park_period: Duration,
+    ) -> Self {
+        BackoffIdleStrategy {
+            max_spins,
+            max_yields,
+            min_park_period,
+            max_park_period,
+            state: BackoffState::NotIdle,
+            spins: 0,
+            yields: 0,
+            park_period: min_park_period,
+        }
+    }
+
+    /// Get the period the strategy will park for the next time it parks
+    pub fn park_period(&self) -> Duration {
+        self.park_period
+    }
+}
+
+impl Default for BackoffIdleStrategy {
+    fn default() -> Self {
+        BackoffIdleStrategy::new(
+            DEFAULT_MAX_SPINS,
+            DEFAULT_MAX_YIELDS,
+            DEFAULT_MIN_PARK_PERIOD,
+            DEFAULT_MAX_PARK_PERIOD,
+        )
+    }
+}
+
+impl IdleStrategy for BackoffIdleStrategy {
+    #[allow(deprecated)]
+    fn idle(&mut self, work_count: i32) {
+        if work_count > 0 {
+            self.reset();
+            return;
+        }
+
+        match self.state {
+            BackoffState::NotIdle => {
+                self.state = BackoffState::Spinning;
+                self.spins += 1;
+            }
+            BackoffState::Spinning => {
+                ::std::sync::atomic::spin_loop_hint();
+                self.spins += 1;
+                if self.spins > self.max_spins {
+                    self.state = BackoffState::Yielding;
+                    self.yields = 0;
+                }
+            }
+            BackoffState::Yielding => {
+                self.yields += 1;
+                if self.yields > self.max_yields {
+                    self.state = BackoffState::Parking;
+                    self.park_period = self.min_park_period;
+                } else {
+                    thread::yield_now();
+                }
+            }
+            BackoffState::Parking => {
+                thread::sleep(self.park_period);
+                self.park_period = ::std::cmp::min(self.park_period * 2, self.max_park_period);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.spins = 0;
+        self.yields = 0;
+        self.park_period = self.min_park_period;
+        self.state = BackoffState::NotIdle;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::idle_strategy::{
+        BackoffIdleStrategy, BackoffState, IdleStrategy, SleepingIdleStrategy,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn backoff_should_progress_through_states() {
+        let park = Duration::from_micros(1);
+        let mut strategy = BackoffIdleStrategy::new(2, 2, park, park * 4);
+
+        strategy.idle(0);
+        assert_eq!(strategy.state, BackoffState::Spinning);
+        strategy.idle(0);
+        strategy.idle(0);
+        assert_eq!(strategy.state, BackoffState::Yielding);
+        strategy.idle(0);
+        strategy.idle(0);
+        strategy.idle(0);
+        assert_eq!(strategy.state, BackoffState::Parking);
+        assert_eq!(strategy.park_period(), park);
+    }
+
+    #[test]
+    fn backoff_should_double_park_period_up_to_max() {
+        let park = Duration::from_micros(1);
+        let mut strategy = BackoffIdleStrategy::new(0, 0, park, park * 4);
+
+        // Spin and yield phases are skipped immediately
+        for _ in 0..3 {
+            strategy.idle(0);
+        }
+        assert_eq!(strategy.state, BackoffState::Parking);
+
+        strategy.idle(0);
+        assert_eq!(strategy.park_period(), park * 2);
+        strategy.idle(0);
+        assert_eq!(strategy.park_period(), park * 4);
+        strategy.idle(0);
+        assert_eq!(strategy.park_period(), park * 4);
+    }
+
+    #[test]
+    fn backoff_should_reset_when_work_done() {
+        let park = Duration::from_micros(1);
+        let mut strategy = BackoffIdleStrategy::new(0, 0, park, park * 4);
+        for _ in 0..5 {
+            strategy.idle(0);
+        }
+
+        strategy.idle(1);
+        assert_eq!(strategy.state, BackoffState::NotIdle);
+        assert_eq!(strategy.park_period(), park);
+    }
+
+    #[test]
+    fn sleeping_should_only_sleep_without_work() {
+        let mut strategy = SleepingIdleStrategy::new(Duration::from_millis(20));
+
+        let start = Instant::now();
+        strategy.idle(1);
+        assert!(start.elapsed() < Duration::from_millis(20));
+
+        strategy.idle(0);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -1,20 +1,30 @@
 //! Module for handling safe interactions among the multiple clients making use
 //! of a single Media Driver
 
+pub mod agent;
 pub mod broadcast;
+pub mod counters;
+pub mod errors;
+pub mod idle_strategy;
 pub mod ringbuffer;
 use std::mem::size_of;
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicI32, AtomicI64, Ordering};
 
 use crate::util::{AeronError, IndexT, Result};
-use std::ptr::{read_volatile, write_volatile};
+use std::ptr::{self, read_volatile, write_volatile, NonNull};
 
 use memmap::MmapMut;
 use std::ops::{Deref, DerefMut};
+use std::slice;
+use std::sync::Arc;
 
 fn bounds_check_slice(slice: &[u8], offset: IndexT, size: IndexT) -> Result<()> {
     if offset < 0 || size < 0 || slice.len() as IndexT - offset < size {
-        Err(AeronError::OutOfBounds)
+        Err(AeronError::OutOfBounds {
+            offset,
+            length: size,
+            capacity: slice.len() as IndexT,
+        })
     } else {
         Ok(())
     }
@@ -264,6 +274,19 @@ pub trait AtomicBuffer: Deref<Target = [u8]> + DerefMut<Target = [u8]> {
         self.overlay_volatile::<i32>(offset)
     }
 
+    /// Perform an atomic fetch and add of a 32-bit value
+    ///
+    /// ```rust
+    /// # use aeron_rs::concurrent::AtomicBuffer;
+    /// let mut buf = vec![0u8; 4];
+    /// assert_eq!(buf.get_and_add_i32(0, 1), Ok(0));
+    /// assert_eq!(buf.get_and_add_i32(0, 1), Ok(1));
+    /// ```
+    fn get_and_add_i32(&self, offset: IndexT, value: i32) -> Result<i32> {
+        self.overlay::<AtomicI32>(offset)
+            .map(|a| a.fetch_add(value, Ordering::SeqCst))
+    }
+
     /// Read an `i32` value from the buffer without performing any synchronization
     fn get_i32(&self, offset: IndexT) -> Result<i32> {
         self.overlay::<i32>(offset).map(|i| *i)
@@ -304,3 +327,184 @@ impl AtomicBuffer for Vec<u8> {}
 impl AtomicBuffer for &mut [u8] {}
 
 impl AtomicBuffer for MmapMut {}
+
+/// View of a region within a shared memory mapping, such as the CnC file shared with
+/// the media driver. Many views may refer to the same mapping, the same way that many
+/// processes map the same file.
+///
+/// The `AtomicBuffer` operations on a view access the mapping through a raw pointer,
+/// and never create references to the underlying bytes beyond the value being read
+/// or written. Synchronization happens through the atomic and volatile operations,
+/// the same as for memory shared with the media driver.
+pub struct MappedBuffer {
+    // Keeps the mapping alive for as long as any view refers to it
+    _mmap: Arc<MmapMut>,
+    ptr: NonNull<u8>,
+    length: usize,
+}
+
+// SAFETY: The mapping is owned by the `Arc` and never moves, so the pointer stays valid
+// on any thread. Concurrent access is covered by the contract of `new` and `view`.
+unsafe impl Send for MappedBuffer {}
+unsafe impl Sync for MappedBuffer {}
+
+impl MappedBuffer {
+    /// Create a view over the entirety of a memory mapping
+    ///
+    /// # Safety
+    ///
+    /// The mapping is shared between this view, any views created from it, and
+    /// any other process mapping the same file. Callers must ensure that slices
+    /// obtained through `Deref` or `DerefMut` are not alive while another view or
+    /// process writes to the same bytes. Concurrent access must go through the
+    /// atomic and volatile operations of `AtomicBuffer`.
+    pub unsafe fn new(mut mmap: MmapMut) -> Self {
+        let length = mmap.len();
+        // UNWRAP: Memory maps are never backed by a null pointer
+        let ptr = NonNull::new(mmap.as_mut_ptr()).unwrap();
+        MappedBuffer {
+            _mmap: Arc::new(mmap),
+            ptr,
+            length,
+        }
+    }
+
+    /// Create a new view of `length` bytes beginning at `offset` within this view.
+    /// Views may overlap each other.
+    ///
+    /// # Safety
+    ///
+    /// The new view aliases this one; the contract described for `new` applies to it.
+    pub unsafe fn view(&self, offset: IndexT, length: IndexT) -> Result<MappedBuffer> {
+        self.bounds_check(offset, length)?;
+        Ok(MappedBuffer {
+            _mmap: self._mmap.clone(),
+            ptr: NonNull::new_unchecked(self.ptr.as_ptr().add(offset as usize)),
+            length: length as usize,
+        })
+    }
+
+    // Pointer to `offset`, once bounds have been checked
+    fn ptr_at(&self, offset: IndexT) -> *mut u8 {
+        // SAFETY: Callers have checked `offset` against the length of this view
+        unsafe { self.ptr.as_ptr().add(offset as usize) }
+    }
+}
+
+impl Deref for MappedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Bounds checked during `view`; aliasing covered by the contract of `new`
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.length) }
+    }
+}
+
+impl DerefMut for MappedBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Bounds checked during `view`; aliasing covered by the contract of `new`
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.length) }
+    }
+}
+
+impl AtomicBuffer for MappedBuffer {
+    fn bounds_check(&self, offset: IndexT, size: IndexT) -> Result<()> {
+        let capacity = self.capacity();
+        if offset < 0 || size < 0 || capacity - offset < size {
+            Err(AeronError::OutOfBounds {
+                offset,
+                length: size,
+                capacity,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn overlay<T>(&self, offset: IndexT) -> Result<&T>
+    where
+        T: Sized,
+    {
+        self.bounds_check(offset, size_of::<T>() as IndexT)?;
+        Ok(unsafe { &*(self.ptr_at(offset) as *const T) })
+    }
+
+    fn overlay_mut<T>(&mut self, offset: IndexT) -> Result<&mut T>
+    where
+        T: Sized,
+    {
+        self.bounds_check(offset, size_of::<T>() as IndexT)?;
+        Ok(unsafe { &mut *(self.ptr_at(offset) as *mut T) })
+    }
+
+    fn overlay_volatile<T>(&self, offset: IndexT) -> Result<T>
+    where
+        T: Copy,
+    {
+        self.bounds_check(offset, size_of::<T>() as IndexT)?;
+        Ok(unsafe { read_volatile(self.ptr_at(offset) as *const T) })
+    }
+
+    fn write_volatile<T>(&mut self, offset: IndexT, val: T) -> Result<()>
+    where
+        T: Copy,
+    {
+        self.bounds_check(offset, size_of::<T>() as IndexT)?;
+        unsafe { write_volatile(self.ptr_at(offset) as *mut T, val) };
+        Ok(())
+    }
+
+    fn put_slice(
+        &mut self,
+        index: IndexT,
+        source: &[u8],
+        source_index: IndexT,
+        len: IndexT,
+    ) -> Result<()> {
+        self.bounds_check(index, len)?;
+        bounds_check_slice(source, source_index, len)?;
+
+        unsafe {
+            ptr::copy(
+                source.as_ptr().add(source_index as usize),
+                self.ptr_at(index),
+                len as usize,
+            )
+        };
+        Ok(())
+    }
+
+    fn put_bytes<B>(
+        &mut self,
+        index: IndexT,
+        source: &B,
+        source_index: IndexT,
+        len: IndexT,
+    ) -> Result<()>
+    where
+        B: AtomicBuffer,
+    {
+        self.bounds_check(index, len)?;
+        source.bounds_check(source_index, len)?;
+
+        // Source and destination may be views over the same mapping
+        unsafe {
+            ptr::copy(
+                source.as_ptr().add(source_index as usize),
+                self.ptr_at(index),
+                len as usize,
+            )
+        };
+        Ok(())
+    }
+
+    fn set_memory(&mut self, offset: IndexT, length: usize, value: u8) -> Result<()> {
+        self.bounds_check(offset, length as IndexT)?;
+        unsafe { self.ptr_at(offset).write_bytes(value, length) };
+        Ok(())
+    }
+
+    fn capacity(&self) -> IndexT {
+        self.length as IndexT
+    }
+}
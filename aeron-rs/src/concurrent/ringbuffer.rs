@@ -37,7 +37,9 @@ pub mod buffer_descriptor {
         if is_power_of_two(capacity) {
             Ok(())
         } else {
-            Err(IllegalArgument)
+            Err(IllegalArgument(
+                "ring buffer capacity must be a power of two",
+            ))
         }
     }
 }
@@ -85,7 +87,9 @@ pub mod record_descriptor {
 
     pub(super) fn check_msg_type_id(msg_type_id: i32) -> Result<()> {
         if msg_type_id < 1 {
-            Err(AeronError::IllegalArgument)
+            Err(AeronError::IllegalArgument(
+                "message type id must be greater than zero",
+            ))
         } else {
             Ok(())
         }
@@ -116,12 +120,81 @@ pub mod record_descriptor {
 
 const INSUFFICIENT_CAPACITY: IndexT = -2;
 
-/// Multi-producer, single-consumer ring buffer implementation.
-pub struct ManyToOneRingBuffer<A>
-where
-    A: AtomicBuffer,
-{
-    buffer: A,
+/// Common interface for ring buffers, allowing code to be generic over whether
+/// there are one or many producers writing into the buffer.
+pub trait RingBuffer {
+    /// Return the total number of bytes in this buffer
+    fn capacity(&self) -> IndexT;
+
+    /// Write a message into the ring buffer. Returns `Ok(false)` if there is
+    /// insufficient space available for the message.
+    fn write<B>(
+        &mut self,
+        msg_type_id: i32,
+        source: &B,
+        source_index: IndexT,
+        length: IndexT,
+    ) -> Result<bool>
+    where
+        B: AtomicBuffer;
+
+    /// Read messages from the ring buffer and dispatch to `handler`, up to `message_count_limit`.
+    /// The handler is given the message type identifier and message body as arguments.
+    ///
+    /// NOTE: The C++ API will stop reading and clean up if an exception is thrown in the handler
+    /// function; by contrast, the Rust API makes no attempt to catch panics and currently
+    /// has no way of stopping reading once started.
+    fn read_n<F>(&mut self, handler: F, message_count_limit: usize) -> Result<usize>
+    where
+        F: FnMut(i32, &[u8]) -> ();
+
+    /// Read messages from the ring buffer and dispatch to `handler`, up to `message_count_limit`
+    /// The handler is given the message type identifier and message body as arguments.
+    ///
+    /// NOTE: The C++ API will stop reading and clean up if an exception is thrown in the handler
+    /// function; by contrast, the Rust API makes no attempt to catch panics and currently
+    /// has no way of stopping reading once started.
+    fn read<F>(&mut self, handler: F) -> Result<usize>
+    where
+        F: FnMut(i32, &[u8]) -> (),
+    {
+        self.read_n(handler, usize::max_value())
+    }
+
+    /// Return the largest possible message size for this buffer
+    fn max_msg_length(&self) -> IndexT;
+
+    /// Atomically retrieve the next correlation identifier. Used as a unique identifier for
+    /// interactions with the Media Driver
+    fn next_correlation_id(&self) -> i64;
+
+    /// Return the last heartbeat timestamp associated with the consumer of this queue.
+    /// Timestamps are milliseconds since 1 Jan 1970, UTC.
+    fn consumer_heartbeat_time(&self) -> i64;
+
+    /// Set the time of the last consumer heartbeat. Consumers should call this periodically
+    /// to indicate liveness to producers. Timestamps are milliseconds since 1 Jan 1970, UTC.
+    fn set_consumer_heartbeat_time(&mut self, time: i64);
+
+    /// Return the current position of the producer, as measured in bytes
+    /// written since the buffer was created.
+    fn producer_position(&self) -> i64;
+
+    /// Return the current position of the consumer, as measured in bytes
+    /// read since the buffer was created.
+    fn consumer_position(&self) -> i64;
+
+    /// Return the number of bytes currently waiting to be read. Gives a consistent
+    /// snapshot even while producers and the consumer are concurrently active.
+    fn size(&self) -> IndexT;
+
+    /// Unblock a ring buffer where a producer has died during the act of offering.
+    /// Returns `Ok(true)` if a record was unblocked, `Ok(false)` otherwise.
+    fn unblock(&mut self) -> Result<bool>;
+}
+
+/// Offsets of the ring buffer metadata fields, shared by all ring buffer implementations.
+struct Layout {
     capacity: IndexT,
     max_msg_length: IndexT,
     tail_position_index: IndexT,
@@ -131,16 +204,14 @@ where
     consumer_heartbeat_index: IndexT,
 }
 
-impl<A> ManyToOneRingBuffer<A>
-where
-    A: AtomicBuffer,
-{
-    /// Create a many-to-one ring buffer from an underlying atomic buffer.
-    pub fn new(buffer: A) -> Result<Self> {
+impl Layout {
+    fn new<A>(buffer: &A) -> Result<Self>
+    where
+        A: AtomicBuffer,
+    {
         let capacity = buffer.capacity() - buffer_descriptor::TRAILER_LENGTH;
         buffer_descriptor::check_capacity(capacity)?;
-        Ok(ManyToOneRingBuffer {
-            buffer,
+        Ok(Layout {
             capacity,
             max_msg_length: capacity / 8,
             tail_position_index: capacity + buffer_descriptor::TAIL_POSITION_OFFSET,
@@ -151,77 +222,28 @@ where
         })
     }
 
-    /// Atomically retrieve the next correlation identifier. Used as a unique identifier for
-    /// interactions with the Media Driver
-    pub fn next_correlation_id(&self) -> i64 {
-        // UNWRAP: Known-valid offset calculated during initialization
-        self.buffer
-            .get_and_add_i64(self.correlation_id_counter_index, 1)
-            .unwrap()
-    }
-
-    /// Return the total number of bytes in this buffer
-    pub fn capacity(&self) -> IndexT {
-        self.capacity
-    }
-
-    /// Write a message into the ring buffer
-    pub fn write<B>(
-        &mut self,
-        msg_type_id: i32,
-        source: &B,
-        source_index: IndexT,
-        length: IndexT,
-    ) -> Result<bool>
-    where
-        B: AtomicBuffer,
-    {
-        record_descriptor::check_msg_type_id(msg_type_id)?;
-        self.check_msg_length(length)?;
-
-        let record_len = length + record_descriptor::HEADER_LENGTH;
-        let required = bit::align(record_len as usize, record_descriptor::ALIGNMENT as usize);
-        let record_index = self.claim_capacity(required as IndexT)?;
-
-        if record_index == INSUFFICIENT_CAPACITY {
-            return Ok(false);
+    fn check_msg_length(&self, length: IndexT) -> Result<()> {
+        if length > self.max_msg_length {
+            Err(AeronError::InsufficientCapacity {
+                required: length,
+                capacity: self.max_msg_length,
+            })
+        } else {
+            Ok(())
         }
-
-        // UNWRAP: `claim_capacity` performed bounds checking
-        self.buffer
-            .put_i64_ordered(
-                record_index,
-                record_descriptor::make_header(-length, msg_type_id),
-            )
-            .unwrap();
-        // UNWRAP: `claim_capacity` performed bounds checking
-        self.buffer
-            .put_bytes(
-                record_descriptor::encoded_msg_offset(record_index),
-                source,
-                source_index,
-                length,
-            )
-            .unwrap();
-        // UNWRAP: `claim_capacity` performed bounds checking
-        self.buffer
-            .put_i32_ordered(record_descriptor::length_offset(record_index), record_len)
-            .unwrap();
-
-        Ok(true)
     }
 
-    /// Read messages from the ring buffer and dispatch to `handler`, up to `message_count_limit`.
-    /// The handler is given the message type identifier and message body as arguments.
-    ///
-    /// NOTE: The C++ API will stop reading and clean up if an exception is thrown in the handler
-    /// function; by contrast, the Rust API makes no attempt to catch panics and currently
-    /// has no way of stopping reading once started.
-    pub fn read_n<F>(&mut self, mut handler: F, message_count_limit: usize) -> Result<usize>
+    fn read_n<A, F>(
+        &self,
+        buffer: &mut A,
+        mut handler: F,
+        message_count_limit: usize,
+    ) -> Result<usize>
     where
+        A: AtomicBuffer,
         F: FnMut(i32, &[u8]) -> (),
     {
-        let head = self.buffer.get_i64(self.head_position_index)?;
+        let head = buffer.get_i64(self.head_position_index)?;
         let head_index = (head & i64::from(self.capacity - 1)) as i32;
         let contiguous_block_length = self.capacity - head_index;
         let mut messages_read = 0;
@@ -230,7 +252,7 @@ where
         let result: Result<()> = (|| {
             while bytes_read < contiguous_block_length && messages_read < message_count_limit {
                 let record_index = head_index + bytes_read;
-                let header = self.buffer.get_i64_volatile(record_index)?;
+                let header = buffer.get_i64_volatile(record_index)?;
                 let record_length = record_descriptor::record_length(header);
 
                 if record_length <= 0 {
@@ -252,7 +274,7 @@ where
                 let msg_start = record_descriptor::encoded_msg_offset(record_index) as usize;
                 let msg_end =
                     msg_start + (record_length - record_descriptor::HEADER_LENGTH) as usize;
-                handler(msg_type_id, &self.buffer[msg_start..msg_end]);
+                handler(msg_type_id, &buffer[msg_start..msg_end]);
             }
             Ok(())
         })();
@@ -265,10 +287,10 @@ where
                 // UNWRAP: Need to justify this one.
                 // Should be safe because we've already done length checks, but I want
                 // to spend some more time thinking about it.
-                self.buffer
+                buffer
                     .set_memory(head_index, bytes_read as usize, 0)
                     .unwrap();
-                self.buffer
+                buffer
                     .put_i64_ordered(self.head_position_index, head + i64::from(bytes_read))
                     .unwrap();
             }
@@ -281,17 +303,186 @@ where
         Ok(messages_read)
     }
 
-    /// Read messages from the ring buffer and dispatch to `handler`, up to `message_count_limit`
+    fn producer_position<A>(&self, buffer: &A) -> i64
+    where
+        A: AtomicBuffer,
+    {
+        // UNWRAP: Known-valid offset calculated during initialization
+        buffer.get_i64_volatile(self.tail_position_index).unwrap()
+    }
+
+    fn consumer_position<A>(&self, buffer: &A) -> i64
+    where
+        A: AtomicBuffer,
+    {
+        // UNWRAP: Known-valid offset calculated during initialization
+        buffer.get_i64_volatile(self.head_position_index).unwrap()
+    }
+
+    fn size<A>(&self, buffer: &A) -> IndexT
+    where
+        A: AtomicBuffer,
+    {
+        let mut head_before: i64;
+        let mut tail: i64;
+        let mut head_after = self.consumer_position(buffer);
+
+        // Re-read until the head has not moved while reading the tail, so the
+        // two values describe the same moment in time.
+        while {
+            head_before = head_after;
+            tail = self.producer_position(buffer);
+            head_after = self.consumer_position(buffer);
+
+            head_after != head_before
+        } {}
+
+        let size = tail - head_after;
+        if size < 0 {
+            0
+        } else if size > i64::from(self.capacity) {
+            self.capacity
+        } else {
+            size as IndexT
+        }
+    }
+
+    fn next_correlation_id<A>(&self, buffer: &A) -> i64
+    where
+        A: AtomicBuffer,
+    {
+        // UNWRAP: Known-valid offset calculated during initialization
+        buffer
+            .get_and_add_i64(self.correlation_id_counter_index, 1)
+            .unwrap()
+    }
+
+    fn consumer_heartbeat_time<A>(&self, buffer: &A) -> i64
+    where
+        A: AtomicBuffer,
+    {
+        // UNWRAP: Known-valid offset calculated during initialization
+        buffer
+            .get_i64_volatile(self.consumer_heartbeat_index)
+            .unwrap()
+    }
+
+    fn set_consumer_heartbeat_time<A>(&self, buffer: &mut A, time: i64)
+    where
+        A: AtomicBuffer,
+    {
+        // UNWRAP: Known-valid offset calculated during initialization
+        buffer
+            .put_i64_ordered(self.consumer_heartbeat_index, time)
+            .unwrap()
+    }
+}
+
+/// Multi-producer, single-consumer ring buffer implementation.
+pub struct ManyToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    buffer: A,
+    layout: Layout,
+}
+
+impl<A> ManyToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a many-to-one ring buffer from an underlying atomic buffer.
+    pub fn new(buffer: A) -> Result<Self> {
+        let layout = Layout::new(&buffer)?;
+        Ok(ManyToOneRingBuffer { buffer, layout })
+    }
+
+    // The methods below forward to `RingBuffer`, so existing callers don't need the
+    // trait in scope.
+
+    /// Atomically retrieve the next correlation identifier. Used as a unique identifier for
+    /// interactions with the Media Driver
+    pub fn next_correlation_id(&self) -> i64 {
+        RingBuffer::next_correlation_id(self)
+    }
+
+    /// Return the total number of bytes in this buffer
+    pub fn capacity(&self) -> IndexT {
+        RingBuffer::capacity(self)
+    }
+
+    /// Write a message into the ring buffer. Returns `Ok(false)` if there is
+    /// insufficient space available for the message.
+    pub fn write<B>(
+        &mut self,
+        msg_type_id: i32,
+        source: &B,
+        source_index: IndexT,
+        length: IndexT,
+    ) -> Result<bool>
+    where
+        B: AtomicBuffer,
+    {
+        RingBuffer::write(self, msg_type_id, source, source_index, length)
+    }
+
+    /// Read messages from the ring buffer and dispatch to `handler`, up to `message_count_limit`.
+    /// The handler is given the message type identifier and message body as arguments.
+    pub fn read_n<F>(&mut self, handler: F, message_count_limit: usize) -> Result<usize>
+    where
+        F: FnMut(i32, &[u8]),
+    {
+        RingBuffer::read_n(self, handler, message_count_limit)
+    }
+
+    /// Read all available messages from the ring buffer and dispatch to `handler`.
     /// The handler is given the message type identifier and message body as arguments.
-    ///
-    /// NOTE: The C++ API will stop reading and clean up if an exception is thrown in the handler
-    /// function; by contrast, the Rust API makes no attempt to catch panics and currently
-    /// has no way of stopping reading once started.
     pub fn read<F>(&mut self, handler: F) -> Result<usize>
     where
-        F: FnMut(i32, &[u8]) -> (),
+        F: FnMut(i32, &[u8]),
     {
-        self.read_n(handler, usize::max_value())
+        RingBuffer::read(self, handler)
+    }
+
+    /// Unblock a multi-producer ring buffer where a producer has died during the act
+    /// of offering. Returns `Ok(true)` if a record was unblocked, `Ok(false)` otherwise.
+    pub fn unblock(&mut self) -> Result<bool> {
+        RingBuffer::unblock(self)
+    }
+
+    /// Return the largest possible message size for this buffer
+    pub fn max_msg_length(&self) -> IndexT {
+        RingBuffer::max_msg_length(self)
+    }
+
+    /// Set the time of the last consumer heartbeat. Consumers should call this periodically
+    /// to indicate liveness to producers. Timestamps are milliseconds since 1 Jan 1970, UTC.
+    pub fn set_consumer_heartbeat_time(&mut self, time: i64) {
+        RingBuffer::set_consumer_heartbeat_time(self, time)
+    }
+
+    /// Return the current position of the producer, as measured in bytes
+    /// written since the buffer was created.
+    pub fn producer_position(&self) -> i64 {
+        RingBuffer::producer_position(self)
+    }
+
+    /// Return the current position of the consumer, as measured in bytes
+    /// read since the buffer was created.
+    pub fn consumer_position(&self) -> i64 {
+        RingBuffer::consumer_position(self)
+    }
+
+    /// Return the number of bytes currently waiting to be read. Gives a consistent
+    /// snapshot even while producers and the consumer are concurrently active.
+    pub fn size(&self) -> IndexT {
+        RingBuffer::size(self)
+    }
+
+    /// Return the last heartbeat timestamp associated with the consumer of this queue.
+    /// Timestamps are milliseconds since 1 Jan 1970, UTC.
+    pub fn consumer_heartbeat_time(&self) -> i64 {
+        RingBuffer::consumer_heartbeat_time(self)
     }
 
     /// Claim capacity for a specific message size in the ring buffer. Returns the offset/index
@@ -299,12 +490,13 @@ where
     fn claim_capacity(&mut self, required: IndexT) -> Result<IndexT> {
         // QUESTION: Is this mask how we handle the "ring" in ring buffer?
         // Would explain why we assert buffer capacity is a power of two during initialization
-        let mask: IndexT = self.capacity - 1;
+        let capacity = self.layout.capacity;
+        let mask: IndexT = capacity - 1;
 
         // UNWRAP: Known-valid offset calculated during initialization
         let mut head = self
             .buffer
-            .get_i64_volatile(self.head_cache_position_index)
+            .get_i64_volatile(self.layout.head_cache_position_index)
             .unwrap();
 
         let mut tail: i64;
@@ -312,18 +504,22 @@ where
         let mut padding: IndexT;
         // Note the braces, making this a do-while loop
         while {
-            tail = self.buffer.get_i64_volatile(self.tail_position_index)?;
-            let available_capacity = self.capacity - (tail - head) as IndexT;
+            tail = self
+                .buffer
+                .get_i64_volatile(self.layout.tail_position_index)?;
+            let available_capacity = capacity - (tail - head) as IndexT;
 
             if required > available_capacity {
-                head = self.buffer.get_i64_volatile(self.head_position_index)?;
+                head = self
+                    .buffer
+                    .get_i64_volatile(self.layout.head_position_index)?;
 
-                if required > (self.capacity - (tail - head) as IndexT) {
+                if required > (capacity - (tail - head) as IndexT) {
                     return Ok(INSUFFICIENT_CAPACITY);
                 }
 
                 self.buffer
-                    .put_i64_ordered(self.head_cache_position_index, head)?;
+                    .put_i64_ordered(self.layout.head_cache_position_index, head)?;
             }
 
             padding = 0;
@@ -332,13 +528,15 @@ where
             // it's "safe" to widen the types and bitmask below. We're just trying
             // to imitate C++ here.
             tail_index = (tail & i64::from(mask)) as IndexT;
-            let to_buffer_end_length = self.capacity - tail_index;
+            let to_buffer_end_length = capacity - tail_index;
 
             if required > to_buffer_end_length {
                 let mut head_index = (head & i64::from(mask)) as IndexT;
 
                 if required > head_index {
-                    head = self.buffer.get_i64_volatile(self.head_position_index)?;
+                    head = self
+                        .buffer
+                        .get_i64_volatile(self.layout.head_position_index)?;
                     head_index = (head & i64::from(mask)) as IndexT;
 
                     if required > head_index {
@@ -346,14 +544,14 @@ where
                     }
 
                     self.buffer
-                        .put_i64_ordered(self.head_cache_position_index, head)?;
+                        .put_i64_ordered(self.layout.head_cache_position_index, head)?;
                 }
 
                 padding = to_buffer_end_length;
             }
 
             !self.buffer.compare_and_set_i64(
-                self.tail_position_index,
+                self.layout.tail_position_index,
                 tail,
                 tail + i64::from(required) + i64::from(padding),
             )?
@@ -370,26 +568,197 @@ where
         Ok(tail_index)
     }
 
-    fn check_msg_length(&self, length: IndexT) -> Result<()> {
-        if length > self.max_msg_length {
-            Err(AeronError::IllegalArgument)
-        } else {
-            Ok(())
+    // Mark a claimed record as in progress. The negative length must span the whole
+    // record, header included, so that `unblock` pads out exactly the claimed space if
+    // the producer dies before publishing.
+    fn begin_record(
+        &mut self,
+        record_index: IndexT,
+        record_len: IndexT,
+        msg_type_id: i32,
+    ) -> Result<()> {
+        self.buffer.put_i64_ordered(
+            record_index,
+            record_descriptor::make_header(-record_len, msg_type_id),
+        )
+    }
+
+    fn scan_back_to_confirm_still_zeroed(&self, from: IndexT, limit: IndexT) -> Result<bool> {
+        let mut i = from - record_descriptor::ALIGNMENT;
+        while i >= limit {
+            if self
+                .buffer
+                .get_i32_volatile(record_descriptor::length_offset(i))?
+                != 0
+            {
+                return Ok(false);
+            }
+            i -= record_descriptor::ALIGNMENT;
         }
+        Ok(true)
     }
+}
 
-    /// Return the largest possible message size for this buffer
-    pub fn max_msg_length(&self) -> IndexT {
-        self.max_msg_length
+impl<A> RingBuffer for ManyToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    fn capacity(&self) -> IndexT {
+        self.layout.capacity
     }
 
-    /// Return the last heartbeat timestamp associated with the consumer of this queue.
-    /// Timestamps are milliseconds since 1 Jan 1970, UTC.
-    pub fn consumer_heartbeat_time(&self) -> i64 {
-        // UNWRAP: Known-valid offset calculated during initialization
+    fn write<B>(
+        &mut self,
+        msg_type_id: i32,
+        source: &B,
+        source_index: IndexT,
+        length: IndexT,
+    ) -> Result<bool>
+    where
+        B: AtomicBuffer,
+    {
+        record_descriptor::check_msg_type_id(msg_type_id)?;
+        self.layout.check_msg_length(length)?;
+
+        let record_len = length + record_descriptor::HEADER_LENGTH;
+        let required = bit::align(record_len as usize, record_descriptor::ALIGNMENT as usize);
+        let record_index = self.claim_capacity(required as IndexT)?;
+
+        if record_index == INSUFFICIENT_CAPACITY {
+            return Ok(false);
+        }
+
+        // UNWRAP: `claim_capacity` performed bounds checking
+        self.begin_record(record_index, record_len, msg_type_id)
+            .unwrap();
+        // UNWRAP: `claim_capacity` performed bounds checking
         self.buffer
-            .get_i64_volatile(self.consumer_heartbeat_index)
-            .unwrap()
+            .put_bytes(
+                record_descriptor::encoded_msg_offset(record_index),
+                source,
+                source_index,
+                length,
+            )
+            .unwrap();
+        // UNWRAP: `claim_capacity` performed bounds checking
+        self.buffer
+            .put_i32_ordered(record_descriptor::length_offset(record_index), record_len)
+            .unwrap();
+
+        Ok(true)
+    }
+
+    fn read_n<F>(&mut self, handler: F, message_count_limit: usize) -> Result<usize>
+    where
+        F: FnMut(i32, &[u8]) -> (),
+    {
+        self.layout
+            .read_n(&mut self.buffer, handler, message_count_limit)
+    }
+
+    fn max_msg_length(&self) -> IndexT {
+        self.layout.max_msg_length
+    }
+
+    fn next_correlation_id(&self) -> i64 {
+        self.layout.next_correlation_id(&self.buffer)
+    }
+
+    fn consumer_heartbeat_time(&self) -> i64 {
+        self.layout.consumer_heartbeat_time(&self.buffer)
+    }
+
+    fn set_consumer_heartbeat_time(&mut self, time: i64) {
+        self.layout
+            .set_consumer_heartbeat_time(&mut self.buffer, time)
+    }
+
+    fn producer_position(&self) -> i64 {
+        self.layout.producer_position(&self.buffer)
+    }
+
+    fn consumer_position(&self) -> i64 {
+        self.layout.consumer_position(&self.buffer)
+    }
+
+    fn size(&self) -> IndexT {
+        self.layout.size(&self.buffer)
+    }
+
+    /// Unblock a multi-producer ring buffer where a producer has died during the act
+    /// of offering. The operation will scan from the consumer position up to the producer
+    /// position, and will convert the first stuck record it finds into padding.
+    /// Returns `Ok(true)` if a record was unblocked, `Ok(false)` otherwise.
+    ///
+    /// If a producer dies after claiming capacity but before writing the record length,
+    /// the consumer will be blocked forever waiting on that record. The media driver
+    /// handles this for the to-driver buffer, but other users of the ring buffer are
+    /// responsible for calling `unblock` if the consumer stops making progress.
+    fn unblock(&mut self) -> Result<bool> {
+        let head = self
+            .buffer
+            .get_i64_volatile(self.layout.head_position_index)?;
+        let tail = self
+            .buffer
+            .get_i64_volatile(self.layout.tail_position_index)?;
+
+        if head == tail {
+            return Ok(false);
+        }
+
+        let capacity = self.layout.capacity;
+        let mask = i64::from(capacity - 1);
+        let consumer_index = (head & mask) as IndexT;
+        let producer_index = (tail & mask) as IndexT;
+
+        let mut unblocked = false;
+        let length = self
+            .buffer
+            .get_i32_volatile(record_descriptor::length_offset(consumer_index))?;
+
+        if length < 0 {
+            // Producer claimed and wrote the header, but never published the record
+            self.buffer.put_i32(
+                record_descriptor::type_offset(consumer_index),
+                record_descriptor::PADDING_MSG_TYPE_ID,
+            )?;
+            self.buffer
+                .put_i32_ordered(record_descriptor::length_offset(consumer_index), -length)?;
+            unblocked = true;
+        } else if length == 0 {
+            // Producer claimed capacity but never wrote the header; scan forward
+            // for the next record header and pad out the gap until it.
+            let limit = if producer_index > consumer_index {
+                producer_index
+            } else {
+                capacity
+            };
+            let mut i = consumer_index + record_descriptor::ALIGNMENT;
+
+            while i < limit {
+                if self
+                    .buffer
+                    .get_i32_volatile(record_descriptor::length_offset(i))?
+                    != 0
+                {
+                    if self.scan_back_to_confirm_still_zeroed(i, consumer_index)? {
+                        self.buffer.put_i32(
+                            record_descriptor::type_offset(consumer_index),
+                            record_descriptor::PADDING_MSG_TYPE_ID,
+                        )?;
+                        self.buffer.put_i32_ordered(
+                            record_descriptor::length_offset(consumer_index),
+                            i - consumer_index,
+                        )?;
+                        unblocked = true;
+                    }
+                    break;
+                }
+                i += record_descriptor::ALIGNMENT;
+            }
+        }
+
+        Ok(unblocked)
     }
 }
 
@@ -413,10 +782,222 @@ where
     }
 }
 
+/// Single-producer, single-consumer ring buffer implementation. Shares the record
+/// and buffer layout of `ManyToOneRingBuffer`, but claims capacity using ordered
+/// stores instead of compare-and-swap, so must never be written to from more than
+/// one thread at a time.
+pub struct OneToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    buffer: A,
+    layout: Layout,
+}
+
+impl<A> OneToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    /// Create a one-to-one ring buffer from an underlying atomic buffer.
+    pub fn new(buffer: A) -> Result<Self> {
+        let layout = Layout::new(&buffer)?;
+        Ok(OneToOneRingBuffer { buffer, layout })
+    }
+
+    /// Claim capacity for a specific message size in the ring buffer. Returns the offset/index
+    /// at which to start writing the next record, and the tail position to publish once
+    /// the record is written, or `None` if there is insufficient capacity.
+    fn claim_capacity(&mut self, required: IndexT) -> Result<Option<(IndexT, i64)>> {
+        let capacity = self.layout.capacity;
+        let mask: IndexT = capacity - 1;
+
+        // Only this producer updates the tail and head cache, so neither needs
+        // a volatile read.
+        let mut head = self.buffer.get_i64(self.layout.head_cache_position_index)?;
+        let tail = self.buffer.get_i64(self.layout.tail_position_index)?;
+        let available_capacity = capacity - (tail - head) as IndexT;
+
+        if required > available_capacity {
+            head = self
+                .buffer
+                .get_i64_volatile(self.layout.head_position_index)?;
+
+            if required > (capacity - (tail - head) as IndexT) {
+                return Ok(None);
+            }
+
+            self.buffer
+                .put_i64(self.layout.head_cache_position_index, head)?;
+        }
+
+        let mut padding: IndexT = 0;
+        let mut tail_index = (tail & i64::from(mask)) as IndexT;
+        let to_buffer_end_length = capacity - tail_index;
+
+        if required > to_buffer_end_length {
+            let mut head_index = (head & i64::from(mask)) as IndexT;
+
+            if required > head_index {
+                head = self
+                    .buffer
+                    .get_i64_volatile(self.layout.head_position_index)?;
+                head_index = (head & i64::from(mask)) as IndexT;
+
+                if required > head_index {
+                    return Ok(None);
+                }
+
+                self.buffer
+                    .put_i64(self.layout.head_cache_position_index, head)?;
+            }
+
+            padding = to_buffer_end_length;
+        }
+
+        // The consumer reads no further than the tail, so the padding record only
+        // becomes visible once the tail is published after the message is written.
+        if padding != 0 {
+            self.buffer.put_i64_ordered(
+                tail_index,
+                record_descriptor::make_header(padding, record_descriptor::PADDING_MSG_TYPE_ID),
+            )?;
+            tail_index = 0;
+        }
+
+        Ok(Some((
+            tail_index,
+            tail + i64::from(required) + i64::from(padding),
+        )))
+    }
+}
+
+impl<A> RingBuffer for OneToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    fn capacity(&self) -> IndexT {
+        self.layout.capacity
+    }
+
+    fn write<B>(
+        &mut self,
+        msg_type_id: i32,
+        source: &B,
+        source_index: IndexT,
+        length: IndexT,
+    ) -> Result<bool>
+    where
+        B: AtomicBuffer,
+    {
+        record_descriptor::check_msg_type_id(msg_type_id)?;
+        self.layout.check_msg_length(length)?;
+
+        let record_len = length + record_descriptor::HEADER_LENGTH;
+        let required = bit::align(record_len as usize, record_descriptor::ALIGNMENT as usize);
+        let (record_index, next_tail) = match self.claim_capacity(required as IndexT)? {
+            Some(claim) => claim,
+            None => return Ok(false),
+        };
+
+        // UNWRAP: `claim_capacity` performed bounds checking
+        self.buffer
+            .put_i64_ordered(
+                record_index,
+                record_descriptor::make_header(-record_len, msg_type_id),
+            )
+            .unwrap();
+        // UNWRAP: `claim_capacity` performed bounds checking
+        self.buffer
+            .put_bytes(
+                record_descriptor::encoded_msg_offset(record_index),
+                source,
+                source_index,
+                length,
+            )
+            .unwrap();
+        // UNWRAP: `claim_capacity` performed bounds checking
+        self.buffer
+            .put_i32_ordered(record_descriptor::length_offset(record_index), record_len)
+            .unwrap();
+        // UNWRAP: Known-valid offset calculated during initialization
+        self.buffer
+            .put_i64_ordered(self.layout.tail_position_index, next_tail)
+            .unwrap();
+
+        Ok(true)
+    }
+
+    fn read_n<F>(&mut self, handler: F, message_count_limit: usize) -> Result<usize>
+    where
+        F: FnMut(i32, &[u8]) -> (),
+    {
+        self.layout
+            .read_n(&mut self.buffer, handler, message_count_limit)
+    }
+
+    fn max_msg_length(&self) -> IndexT {
+        self.layout.max_msg_length
+    }
+
+    fn next_correlation_id(&self) -> i64 {
+        self.layout.next_correlation_id(&self.buffer)
+    }
+
+    fn consumer_heartbeat_time(&self) -> i64 {
+        self.layout.consumer_heartbeat_time(&self.buffer)
+    }
+
+    fn set_consumer_heartbeat_time(&mut self, time: i64) {
+        self.layout
+            .set_consumer_heartbeat_time(&mut self.buffer, time)
+    }
+
+    fn producer_position(&self) -> i64 {
+        self.layout.producer_position(&self.buffer)
+    }
+
+    fn consumer_position(&self) -> i64 {
+        self.layout.consumer_position(&self.buffer)
+    }
+
+    fn size(&self) -> IndexT {
+        self.layout.size(&self.buffer)
+    }
+
+    /// A single producer can never leave another producer blocked, so there is
+    /// nothing to unblock. Always returns `Ok(false)`.
+    fn unblock(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+impl<A> Deref for OneToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl<A> DerefMut for OneToOneRingBuffer<A>
+where
+    A: AtomicBuffer,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::concurrent::ringbuffer::ManyToOneRingBuffer;
+    use crate::concurrent::ringbuffer::{
+        record_descriptor, ManyToOneRingBuffer, OneToOneRingBuffer,
+    };
     use crate::concurrent::AtomicBuffer;
+    use crate::util::{bit, IndexT};
 
     const BUFFER_SIZE: usize = 512 + super::buffer_descriptor::TRAILER_LENGTH as usize;
 
@@ -428,7 +1009,7 @@ mod tests {
         assert_eq!(
             ring_buf
                 .buffer
-                .get_i64_volatile(ring_buf.tail_position_index),
+                .get_i64_volatile(ring_buf.layout.tail_position_index),
             Ok(16)
         );
 
@@ -436,6 +1017,37 @@ mod tests {
         assert_eq!(write_start, 16);
     }
 
+    #[test]
+    fn unblock_pads_whole_record_left_in_progress() {
+        let mut ring_buf = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SIZE]).unwrap();
+
+        // A producer claims and begins a record, then dies before publishing it
+        let length = 12;
+        let record_len = length + record_descriptor::HEADER_LENGTH;
+        let required = bit::align(record_len as usize, record_descriptor::ALIGNMENT as usize);
+        let record_index = ring_buf.claim_capacity(required as IndexT).unwrap();
+        ring_buf.begin_record(record_index, record_len, 7).unwrap();
+        assert_eq!(
+            ring_buf
+                .buffer
+                .get_i32(record_descriptor::length_offset(record_index)),
+            Ok(-record_len)
+        );
+
+        let msg = vec![9u8; 4];
+        assert_eq!(ring_buf.write(8, &msg, 0, 4), Ok(true));
+
+        // Padding must cover the header as well as the body, or the consumer would
+        // resume partway through the stuck record and miss the message after it
+        assert_eq!(ring_buf.unblock(), Ok(true));
+        let mut received = Vec::new();
+        assert_eq!(
+            ring_buf.read(|msg_type_id, msg| received.push((msg_type_id, msg.to_vec()))),
+            Ok(1)
+        );
+        assert_eq!(received, vec![(8, msg)]);
+    }
+
     #[test]
     fn claim_capacity_shared() {
         let buf = &mut [0u8; BUFFER_SIZE][..];
@@ -445,11 +1057,25 @@ mod tests {
         assert_eq!(
             ring_buf
                 .buffer
-                .get_i64_volatile(ring_buf.tail_position_index),
+                .get_i64_volatile(ring_buf.layout.tail_position_index),
             Ok(16)
         );
 
         let write_start = ring_buf.claim_capacity(16).unwrap();
         assert_eq!(write_start, 16);
     }
+
+    #[test]
+    fn claim_capacity_one_to_one() {
+        let mut ring_buf = OneToOneRingBuffer::new(vec![0u8; BUFFER_SIZE]).unwrap();
+
+        // Claiming doesn't publish the tail; that waits until the record is written
+        assert_eq!(ring_buf.claim_capacity(16), Ok(Some((0, 16))));
+        assert_eq!(
+            ring_buf
+                .buffer
+                .get_i64_volatile(ring_buf.layout.tail_position_index),
+            Ok(0)
+        );
+    }
 }
@@ -1,11 +1,42 @@
 //! Client library for Aeron. This encapsulates the logic needed to communicate
 //! with the media driver, but does not manage the media driver itself.
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::idle_strategy::IdleStrategy;
+use crate::concurrent::MappedBuffer;
+use crate::image::Image;
+use crate::util::AeronError;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Default time to wait for the media driver to respond, or to consider it inactive
+pub const DEFAULT_DRIVER_TIMEOUT_MS: i64 = 10_000;
+
+/// Default period the client conductor thread sleeps for when there is no work
+pub const DEFAULT_IDLE_SLEEP_MS: u64 = 16;
+
+/// Handler for notifications about counters. Receives a reader over the counters
+/// buffers, the counter's registration identifier, and the counter identifier.
+/// Counter handlers are called after the client conductor lock is released, so they
+/// may call back into the client, e.g. to add or close counters.
+pub type CounterHandler = Box<dyn FnMut(&CountersReader<MappedBuffer>, i64, i32) + Send>;
+
+/// Handler for notifications about images becoming available to, or being removed
+/// from, a subscription.
+/// Image handlers are called after the client conductor lock is released, so they
+/// may call back into the client, e.g. to query the subscription's images.
+pub type ImageHandler = Box<dyn FnMut(&Image) + Send>;
 
 /// Context used to initialize the Aeron client
 pub struct ClientContext {
-    _aeron_dir: PathBuf,
+    aeron_dir: PathBuf,
+    driver_timeout_ms: i64,
+    use_conductor_agent_invoker: bool,
+    pub(crate) idle_strategy: Option<Box<dyn IdleStrategy + Send>>,
+    pub(crate) error_handler: Option<Box<dyn FnMut(AeronError) + Send>>,
+    pub(crate) available_counter_handler: Option<CounterHandler>,
+    pub(crate) unavailable_counter_handler: Option<CounterHandler>,
+    pub(crate) available_image_handler: Option<ImageHandler>,
+    pub(crate) unavailable_image_handler: Option<ImageHandler>,
 }
 
 impl ClientContext {
@@ -26,12 +57,114 @@ impl ClientContext {
 
         base_path.join(format!("aeron-{}", ClientContext::get_user_name()))
     }
+
+    /// Set the directory used by the Media Driver to interact with clients
+    pub fn set_aeron_dir(mut self, path: &Path) -> Self {
+        self.aeron_dir = path.to_path_buf();
+        self
+    }
+
+    /// Get the directory used by the Media Driver to interact with clients
+    pub fn aeron_dir(&self) -> &Path {
+        &self.aeron_dir
+    }
+
+    /// Set how long to wait for the media driver to respond to a command, and how long
+    /// the driver may go without a heartbeat before it's considered inactive.
+    pub fn set_driver_timeout_ms(mut self, timeout_ms: i64) -> Self {
+        self.driver_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Get the media driver timeout, in milliseconds
+    pub fn driver_timeout_ms(&self) -> i64 {
+        self.driver_timeout_ms
+    }
+
+    /// Choose whether the client conductor runs on its own thread (the default), or is
+    /// driven by the application through `Aeron::conductor_invoker()`. Applications using
+    /// the invoker must invoke it regularly to send keepalives to the media driver.
+    pub fn set_use_conductor_agent_invoker(mut self, use_invoker: bool) -> Self {
+        self.use_conductor_agent_invoker = use_invoker;
+        self
+    }
+
+    /// Check whether the client conductor will be driven by the application
+    pub fn use_conductor_agent_invoker(&self) -> bool {
+        self.use_conductor_agent_invoker
+    }
+
+    /// Set the idle strategy used by the client conductor thread. By default, the thread
+    /// sleeps for `DEFAULT_IDLE_SLEEP_MS` when there is no work.
+    pub fn set_idle_strategy<I>(mut self, idle_strategy: I) -> Self
+    where
+        I: IdleStrategy + Send + 'static,
+    {
+        self.idle_strategy = Some(Box::new(idle_strategy));
+        self
+    }
+
+    /// Set the handler for errors encountered by the client conductor, such as the media
+    /// driver timing out or rejecting a command nobody is waiting on
+    pub fn set_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(AeronError) + Send + 'static,
+    {
+        self.error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the handler notified when any counter becomes available in the media driver
+    pub fn set_available_counter_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&CountersReader<MappedBuffer>, i64, i32) + Send + 'static,
+    {
+        self.available_counter_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the handler notified when any counter is removed from the media driver
+    pub fn set_unavailable_counter_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&CountersReader<MappedBuffer>, i64, i32) + Send + 'static,
+    {
+        self.unavailable_counter_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the handler notified when an image becomes available to a subscription.
+    /// Subscriptions added with their own handlers use those instead.
+    pub fn set_available_image_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&Image) + Send + 'static,
+    {
+        self.available_image_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the handler notified when an image is no longer available to a subscription.
+    /// Subscriptions added with their own handlers use those instead.
+    pub fn set_unavailable_image_handler<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&Image) + Send + 'static,
+    {
+        self.unavailable_image_handler = Some(Box::new(handler));
+        self
+    }
 }
 
 impl Default for ClientContext {
     fn default() -> Self {
         ClientContext {
-            _aeron_dir: ClientContext::default_aeron_path(),
+            aeron_dir: ClientContext::default_aeron_path(),
+            driver_timeout_ms: DEFAULT_DRIVER_TIMEOUT_MS,
+            use_conductor_agent_invoker: false,
+            idle_strategy: None,
+            error_handler: None,
+            available_counter_handler: None,
+            unavailable_counter_handler: None,
+            available_image_handler: None,
+            unavailable_image_handler: None,
         }
     }
 }
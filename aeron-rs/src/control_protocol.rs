@@ -23,7 +23,7 @@ macro_rules! define_enum {
 
         impl ::std::convert::TryFrom<u32> for $name {
             type Error = ();
-            fn try_from(val: u32) -> Result<$name, ()> {
+            fn try_from(val: u32) -> ::std::result::Result<$name, ()> {
                 match val {
                     $(v if v == $name::$left as u32 => Ok($name::$left)),*,
                     _ => Err(())
@@ -93,10 +93,39 @@ define_enum!(
     }
 );
 
+define_enum!(
+    #[doc = "Error codes sent by the Media Driver in an error response"]
+    #[derive(Clone, Copy)]
+    pub enum ErrorCode {
+        #[doc = "Error with no more specific category"]
+        GenericError = AERON_ERROR_CODE_GENERIC_ERROR,
+        #[doc = "Channel string couldn't be parsed or used"]
+        InvalidChannel = AERON_ERROR_CODE_INVALID_CHANNEL,
+        #[doc = "Subscription isn't known to the Media Driver"]
+        UnknownSubscription = AERON_ERROR_CODE_UNKNOWN_SUBSCRIPTION,
+        #[doc = "Publication isn't known to the Media Driver"]
+        UnknownPublication = AERON_ERROR_CODE_UNKNOWN_PUBLICATION,
+        #[doc = "Channel endpoint couldn't be created or used"]
+        ChannelEndpointError = AERON_ERROR_CODE_CHANNEL_ENDPOINT_ERROR,
+        #[doc = "Counter isn't known to the Media Driver"]
+        UnknownCounter = AERON_ERROR_CODE_UNKNOWN_COUNTER,
+        #[doc = "Command type isn't known to the Media Driver"]
+        UnknownCommandTypeId = AERON_ERROR_CODE_UNKNOWN_COMMAND_TYPE_ID,
+        #[doc = "Command couldn't be decoded"]
+        MalformedCommand = AERON_ERROR_CODE_MALFORMED_COMMAND,
+        #[doc = "Operation isn't supported by the Media Driver"]
+        NotSupported = AERON_ERROR_CODE_NOT_SUPPORTED,
+        #[doc = "Host name couldn't be resolved"]
+        UnknownHost = AERON_ERROR_CODE_UNKNOWN_HOST,
+        #[doc = "Resource is temporarily unavailable; retrying may succeed"]
+        ResourceTemporarilyUnavailable = AERON_ERROR_CODE_RESOURCE_TEMPORARILY_UNAVAILABLE,
+    }
+);
+
 #[cfg(test)]
 mod tests {
-    use crate::control_protocol::ClientCommand;
-    use std::convert::TryInto;
+    use crate::control_protocol::{ClientCommand, ErrorCode};
+    use std::convert::{TryFrom, TryInto};
 
     #[test]
     fn client_command_convert() {
@@ -105,4 +134,13 @@ mod tests {
             ::aeron_driver_sys::AERON_COMMAND_ADD_PUBLICATION.try_into()
         )
     }
+
+    #[test]
+    fn error_code_convert() {
+        assert_eq!(
+            Ok(ErrorCode::UnknownCounter),
+            ::aeron_driver_sys::AERON_ERROR_CODE_UNKNOWN_COUNTER.try_into()
+        );
+        assert_eq!(ErrorCode::try_from(1000), Err(()));
+    }
 }
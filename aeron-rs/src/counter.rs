@@ -0,0 +1,94 @@
+//! Counters allocated by the media driver on behalf of a client
+use crate::client_conductor::ClientConductor;
+use crate::concurrent::counters::counter_offset;
+use crate::concurrent::{AtomicBuffer, MappedBuffer};
+use crate::util::{AeronError, IndexT, Result};
+use std::mem::size_of;
+use std::sync::{Arc, Mutex};
+
+/// A counter owned by this client, whose value is visible to any process that
+/// reads the media driver's counters. Updates are written directly into the counters
+/// values buffer. The counter is removed from the media driver when closed or dropped.
+pub struct Counter {
+    registration_id: i64,
+    counter_id: i32,
+    offset: IndexT,
+    values_buffer: MappedBuffer,
+    conductor: Arc<Mutex<ClientConductor>>,
+    is_closed: bool,
+}
+
+impl Counter {
+    pub(crate) fn new(
+        registration_id: i64,
+        counter_id: i32,
+        values_buffer: MappedBuffer,
+        conductor: Arc<Mutex<ClientConductor>>,
+    ) -> Result<Self> {
+        let offset = counter_offset(counter_id);
+        values_buffer.bounds_check(offset, size_of::<i64>() as IndexT)?;
+        Ok(Counter {
+            registration_id,
+            counter_id,
+            offset,
+            values_buffer,
+            conductor,
+            is_closed: false,
+        })
+    }
+
+    /// Get the registration identifier the media driver associated with this counter
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    /// Get the identifier of this counter within the counters buffers
+    pub fn id(&self) -> i32 {
+        self.counter_id
+    }
+
+    /// Atomically increment the counter value, returning the previous value
+    pub fn increment(&self) -> i64 {
+        // UNWRAP: Known-valid offset calculated during initialization
+        self.values_buffer.get_and_add_i64(self.offset, 1).unwrap()
+    }
+
+    /// Set the counter value with ordered (release) semantics
+    pub fn set_ordered(&mut self, value: i64) {
+        // UNWRAP: Known-valid offset calculated during initialization
+        self.values_buffer
+            .put_i64_ordered(self.offset, value)
+            .unwrap()
+    }
+
+    /// Get the latest counter value with volatile semantics
+    pub fn get(&self) -> i64 {
+        // UNWRAP: Known-valid offset calculated during initialization
+        self.values_buffer.get_i64_volatile(self.offset).unwrap()
+    }
+
+    /// Check whether this counter has been closed
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Ask the media driver to remove this counter. Closing an already-closed
+    /// counter has no effect.
+    pub fn close(&mut self) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        self.is_closed = true;
+        self.conductor
+            .lock()
+            .map_err(|_| AeronError::IllegalState("client conductor lock is poisoned"))?
+            .remove_counter(self.registration_id)
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
@@ -4,28 +4,21 @@ use std::ffi::{CStr, CString};
 use std::path::Path;
 use std::ptr;
 
+use crate::concurrent::agent::Agent;
+use crate::util::{AeronError, Result};
 use aeron_driver_sys::*;
 use std::marker::PhantomData;
 use std::mem::replace;
 
-/// Error code and message returned by the Media Driver
-#[derive(Debug, PartialEq)]
-pub struct DriverError {
-    code: i32,
-    msg: String,
-}
-
-type Result<S> = std::result::Result<S, DriverError>;
-
 macro_rules! aeron_op {
     ($op:expr) => {
         if $op < 0 {
             let code = ::aeron_driver_sys::aeron_errcode();
-            let msg = CStr::from_ptr(::aeron_driver_sys::aeron_errmsg())
+            let message = CStr::from_ptr(::aeron_driver_sys::aeron_errmsg())
                 .to_str()
                 .unwrap()
                 .to_string();
-            Err(DriverError { code, msg })
+            Err(AeronError::MediaDriver { code, message })
         } else {
             Ok(())
         }
@@ -150,6 +143,31 @@ impl MediaDriver<DriverStarted> {
             aeron_driver_main_idle_strategy(self.c_driver, aeron_driver_main_do_work(self.c_driver))
         };
     }
+
+    /// Perform a single duty cycle of the Media Driver without idling afterward.
+    /// Returns the amount of work done, so the caller can idle using an `IdleStrategy`
+    /// of their choosing.
+    pub fn do_duty_cycle(&self) -> i32 {
+        unsafe { aeron_driver_main_do_work(self.c_driver) }
+    }
+}
+
+// SAFETY: The C driver's state is reachable only through the pointers owned by this
+// `MediaDriver`, and isn't tied to the thread that created it; it only requires that
+// calls into it aren't made from multiple threads concurrently. Moving the started
+// driver to another thread (e.g. an `AgentRunner`) keeps that guarantee, since the
+// pointers move with it and `MediaDriver` remains `!Sync`. Errors are reported through
+// thread-local state in the C library, which `aeron_op!` reads on the calling thread.
+unsafe impl Send for MediaDriver<DriverStarted> {}
+
+impl Agent for MediaDriver<DriverStarted> {
+    fn do_work(&mut self) -> Result<i32> {
+        Ok(self.do_duty_cycle())
+    }
+
+    fn role_name(&self) -> String {
+        "media-driver".to_string()
+    }
 }
 
 impl<S> Drop for MediaDriver<S> {
@@ -165,8 +183,14 @@ impl<S> Drop for MediaDriver<S> {
 
 #[cfg(test)]
 mod tests {
-    use crate::driver::{DriverContext, DriverError};
+    use crate::concurrent::agent::AgentRunner;
+    use crate::concurrent::idle_strategy::SleepingIdleStrategy;
+    use crate::driver::DriverContext;
+    use crate::util::AeronError;
     use std::ffi::CStr;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
     use tempfile::tempdir;
 
     #[test]
@@ -195,9 +219,9 @@ mod tests {
         assert!(driver_res.is_err());
         assert_eq!(
             driver_res.unwrap_err(),
-            DriverError {
+            AeronError::MediaDriver {
                 code: 0,
-                msg: expected_message
+                message: expected_message
             }
         );
     }
@@ -216,4 +240,32 @@ mod tests {
             .expect("Unable to start driver");
         driver.do_work();
     }
+
+    #[test]
+    fn run_on_agent_runner() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+        temp_dir.close().unwrap();
+
+        let driver = DriverContext::default()
+            .set_aeron_dir(&path)
+            .build()
+            .expect("Unable to create media driver")
+            .start()
+            .expect("Unable to start driver");
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let mut handle = {
+            let errors = errors.clone();
+            AgentRunner::new(driver, SleepingIdleStrategy::new(Duration::from_millis(1)))
+                .set_error_handler(move |error| errors.lock().unwrap().push(error))
+                .start()
+                .expect("Unable to run driver")
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(handle.is_running());
+
+        handle.close().unwrap();
+        assert!(errors.lock().unwrap().is_empty());
+    }
 }
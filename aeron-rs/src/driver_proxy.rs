@@ -1,10 +1,17 @@
 //! High level API for issuing commands to the Media Driver
+use crate::command::correlated_message::CorrelatedMessageDefn;
+use crate::command::counter_message::CounterMessageDefn;
+use crate::command::destination_message::DestinationMessageDefn;
 use crate::command::flyweight::Flyweight;
+use crate::command::publication_message::PublicationMessageDefn;
+use crate::command::remove_message::RemoveMessageDefn;
+use crate::command::subscription_message::SubscriptionMessageDefn;
 use crate::command::terminate_driver::TerminateDriverDefn;
 use crate::concurrent::ringbuffer::ManyToOneRingBuffer;
 use crate::concurrent::AtomicBuffer;
 use crate::control_protocol::ClientCommand;
 use crate::util::{AeronError, IndexT, Result};
+use std::mem::size_of;
 
 /// High-level interface for issuing commands to a media driver
 pub struct DriverProxy<A>
@@ -51,25 +58,211 @@ where
             token_buffer.map(|b| request.put_token_buffer(b));
             *length = request.length();
 
-            ClientCommand::TerminateDriver
+            Ok(ClientCommand::TerminateDriver)
         })
     }
 
+    /// Request the media driver allocate a counter on behalf of this client. Returns
+    /// the correlation identifier of the request, which will also be the registration
+    /// identifier of the counter.
+    pub fn add_counter(&mut self, type_id: i32, key: &[u8], label: &str) -> Result<i64> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<CounterMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id)
+                .put_type_id(type_id);
+            request.put_key_buffer(key)?.put_label(label)?;
+            *length = request.length()?;
+
+            Ok(ClientCommand::AddCounter)
+        })?;
+
+        Ok(correlation_id)
+    }
+
+    /// Request the media driver remove a counter previously added by this client.
+    /// Returns the correlation identifier of the request.
+    pub fn remove_counter(&mut self, registration_id: i64) -> Result<i64> {
+        self.remove_resource(ClientCommand::RemoveCounter, registration_id)
+    }
+
+    /// Request the media driver add a publication on behalf of this client. Returns
+    /// the correlation identifier of the request.
+    pub fn add_publication(&mut self, channel: &str, stream_id: i32) -> Result<i64> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<PublicationMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id)
+                .put_stream_id(stream_id);
+            request.put_channel(channel)?;
+            *length = request.length();
+
+            Ok(ClientCommand::AddPublication)
+        })?;
+
+        Ok(correlation_id)
+    }
+
+    /// Request the media driver remove a publication previously added by this client.
+    /// Returns the correlation identifier of the request.
+    pub fn remove_publication(&mut self, registration_id: i64) -> Result<i64> {
+        self.remove_resource(ClientCommand::RemovePublication, registration_id)
+    }
+
+    /// Request the media driver add a destination to a multi-destination-cast
+    /// publication. Returns the correlation identifier of the request.
+    pub fn add_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        self.write_destination(ClientCommand::AddDestination, registration_id, channel)
+    }
+
+    /// Request the media driver remove a destination from a multi-destination-cast
+    /// publication. Returns the correlation identifier of the request.
+    pub fn remove_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        self.write_destination(ClientCommand::RemoveDestination, registration_id, channel)
+    }
+
+    /// Request the media driver add a subscription on behalf of this client. Returns
+    /// the correlation identifier of the request.
+    pub fn add_subscription(&mut self, channel: &str, stream_id: i32) -> Result<i64> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<SubscriptionMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id)
+                .put_registration_correlation_id(-1)
+                .put_stream_id(stream_id);
+            request.put_channel(channel)?;
+            *length = request.length();
+
+            Ok(ClientCommand::AddSubscription)
+        })?;
+
+        Ok(correlation_id)
+    }
+
+    /// Request the media driver remove a subscription previously added by this client.
+    /// Returns the correlation identifier of the request.
+    pub fn remove_subscription(&mut self, registration_id: i64) -> Result<i64> {
+        self.remove_resource(ClientCommand::RemoveSubscription, registration_id)
+    }
+
+    /// Request the media driver add a destination to a multi-destination subscription.
+    /// Returns the correlation identifier of the request.
+    pub fn add_rcv_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        self.write_destination(ClientCommand::AddRcvDestination, registration_id, channel)
+    }
+
+    /// Request the media driver remove a destination from a multi-destination
+    /// subscription. Returns the correlation identifier of the request.
+    pub fn remove_rcv_destination(&mut self, registration_id: i64, channel: &str) -> Result<i64> {
+        self.write_destination(
+            ClientCommand::RemoveRcvDestination,
+            registration_id,
+            channel,
+        )
+    }
+
+    /// Let the media driver know this client is still active. Clients that don't
+    /// send keepalives within the driver's client liveness timeout are removed.
+    pub fn send_client_keepalive(&mut self) -> Result<()> {
+        let client_id = self.client_id;
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<CorrelatedMessageDefn>(buffer, 0)?;
+
+            request.put_client_id(client_id).put_correlation_id(0);
+            *length = size_of::<CorrelatedMessageDefn>() as IndexT;
+
+            Ok(ClientCommand::ClientKeepalive)
+        })
+    }
+
+    /// Let the media driver know this client is closing, so that any resources
+    /// it still holds can be released.
+    pub fn client_close(&mut self) -> Result<()> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<CorrelatedMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id);
+            *length = size_of::<CorrelatedMessageDefn>() as IndexT;
+
+            Ok(ClientCommand::ClientClose)
+        })
+    }
+
+    fn remove_resource(&mut self, command: ClientCommand, registration_id: i64) -> Result<i64> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<RemoveMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id)
+                .put_registration_id(registration_id);
+            *length = size_of::<RemoveMessageDefn>() as IndexT;
+
+            Ok(command)
+        })?;
+
+        Ok(correlation_id)
+    }
+
+    fn write_destination(
+        &mut self,
+        command: ClientCommand,
+        registration_id: i64,
+        channel: &str,
+    ) -> Result<i64> {
+        let client_id = self.client_id;
+        let correlation_id = self.to_driver.next_correlation_id();
+        self.write_command_to_driver(|buffer: &mut [u8], length: &mut IndexT| {
+            let mut request = Flyweight::new::<DestinationMessageDefn>(buffer, 0)?;
+
+            request
+                .put_client_id(client_id)
+                .put_correlation_id(correlation_id)
+                .put_registration_id(registration_id);
+            request.put_channel(channel)?;
+            *length = request.length();
+
+            Ok(command)
+        })?;
+
+        Ok(correlation_id)
+    }
+
     fn write_command_to_driver<F>(&mut self, filler: F) -> Result<()>
     where
-        F: FnOnce(&mut [u8], &mut IndexT) -> ClientCommand,
+        F: FnOnce(&mut [u8], &mut IndexT) -> Result<ClientCommand>,
     {
         // QUESTION: Can Rust align structs on stack?
         // C++ does some fancy shenanigans I assume help the CPU cache?
         let mut buffer = &mut [0u8; 512][..];
         let mut length = buffer.len() as IndexT;
-        let msg_type_id = filler(&mut buffer, &mut length);
+        let msg_type_id = filler(&mut buffer, &mut length)?;
 
         if !self
             .to_driver
             .write(msg_type_id as i32, &buffer, 0, length)?
         {
-            Err(AeronError::IllegalState)
+            Err(AeronError::IllegalState(
+                "command couldn't be written to the media driver",
+            ))
         } else {
             Ok(())
         }
@@ -0,0 +1,59 @@
+//! Images are the streams a subscription receives, one for each publisher session
+//! matching the subscription's channel and stream.
+
+/// A publisher's session as received by a subscription of this client
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub(crate) correlation_id: i64,
+    pub(crate) subscription_registration_id: i64,
+    pub(crate) session_id: i32,
+    pub(crate) stream_id: i32,
+    pub(crate) source_identity: String,
+    pub(crate) join_position: i64,
+    pub(crate) subscriber_position_id: i32,
+    pub(crate) log_file_name: String,
+}
+
+impl Image {
+    /// Get the identifier the media driver associated with this image
+    pub fn correlation_id(&self) -> i64 {
+        self.correlation_id
+    }
+
+    /// Get the registration identifier of the subscription receiving this image
+    pub fn subscription_registration_id(&self) -> i64 {
+        self.subscription_registration_id
+    }
+
+    /// Get the session identifier of the publisher sending this image
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    /// Get the stream identifier of this image
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    /// Get the identity of the source sending this image. For UDP channels, this is
+    /// the address and port of the sender.
+    pub fn source_identity(&self) -> &str {
+        &self.source_identity
+    }
+
+    /// Get the position in the stream at which the subscription joined this image
+    pub fn join_position(&self) -> i64 {
+        self.join_position
+    }
+
+    /// Get the identifier of the counter tracking how far the subscription has consumed
+    /// this image
+    pub fn subscriber_position_id(&self) -> i32 {
+        self.subscriber_position_id
+    }
+
+    /// Get the name of the file holding this image's log buffers
+    pub fn log_file_name(&self) -> &str {
+        &self.log_file_name
+    }
+}
@@ -4,13 +4,23 @@
 #[cfg(target_endian = "big")]
 compile_error!("Aeron is only supported on little-endian architectures");
 
+pub mod channel_uri;
+pub mod client;
+pub mod client_conductor;
 pub mod cnc_descriptor;
 pub mod command;
 pub mod concurrent;
 pub mod context;
+#[macro_use]
 pub mod control_protocol;
+pub mod counter;
 pub mod driver;
 pub mod driver_proxy;
+pub mod image;
+pub mod loss_report;
+pub mod publication;
+pub mod status;
+pub mod subscription;
 pub mod util;
 
 const fn sematic_version_compose(major: u8, minor: u8, patch: u8) -> i32 {
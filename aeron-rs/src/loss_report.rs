@@ -0,0 +1,172 @@
+//! Read the loss report the media driver writes alongside the CnC file. Each entry
+//! describes the gaps observed on a single stream from a single source.
+use crate::concurrent::{AtomicBuffer, MappedBuffer};
+use crate::util::bit::align;
+use crate::util::{AeronError, IndexT, Result};
+use memmap::MmapOptions;
+use std::fs::OpenOptions;
+use std::mem::size_of;
+use std::path::Path;
+
+/// Filename for the loss report located in the Aeron directory
+pub const LOSS_REPORT_FILE: &str = "loss-report.dat";
+
+/// Description of the loss report entry schema
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    Observation Count                          |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                     Total Bytes Lost                          |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |               First Observation Timestamp                     |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                Last Observation Timestamp                     |
+/// |                                                               |
+/// +---------------------------------------------------------------+
+/// |                          Session ID                           |
+/// +---------------------------------------------------------------+
+/// |                           Stream ID                           |
+/// +---------------------------------------------------------------+
+/// |                 Channel encoded in US-ASCII                  ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// |                  Source encoded in US-ASCII                  ...
+/// ...                                                             |
+/// +---------------------------------------------------------------+
+/// ```
+///
+/// Channel and source are each prefixed with their length as an `i32`, and the source
+/// length is aligned to 4 bytes following the channel.
+pub mod loss_report_descriptor {
+    use crate::util::bit::CACHE_LINE_LENGTH;
+    use crate::util::IndexT;
+    use std::mem::size_of;
+
+    /// Offset from the beginning of an entry to the number of gaps observed
+    pub const OBSERVATION_COUNT_OFFSET: IndexT = 0;
+
+    /// Offset from the beginning of an entry to the total number of bytes lost
+    pub const TOTAL_BYTES_LOST_OFFSET: IndexT =
+        OBSERVATION_COUNT_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Offset from the beginning of an entry to the time loss was first observed
+    pub const FIRST_OBSERVATION_OFFSET: IndexT =
+        TOTAL_BYTES_LOST_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Offset from the beginning of an entry to the time loss was last observed
+    pub const LAST_OBSERVATION_OFFSET: IndexT =
+        FIRST_OBSERVATION_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Offset from the beginning of an entry to the session identifier
+    pub const SESSION_ID_OFFSET: IndexT = LAST_OBSERVATION_OFFSET + size_of::<i64>() as IndexT;
+
+    /// Offset from the beginning of an entry to the stream identifier
+    pub const STREAM_ID_OFFSET: IndexT = SESSION_ID_OFFSET + size_of::<i32>() as IndexT;
+
+    /// Offset from the beginning of an entry to the channel length
+    pub const CHANNEL_OFFSET: IndexT = STREAM_ID_OFFSET + size_of::<i32>() as IndexT;
+
+    /// Alignment for all loss report entries
+    pub const ENTRY_ALIGNMENT: IndexT = CACHE_LINE_LENGTH as IndexT;
+}
+
+/// Loss observed on a single stream from a single source
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossEntry {
+    /// Number of gaps observed
+    pub observation_count: i64,
+    /// Total number of bytes lost across all gaps
+    pub total_bytes_lost: i64,
+    /// Time loss was first observed, as milliseconds since 1 Jan 1970, UTC
+    pub first_observation_timestamp: i64,
+    /// Time loss was last observed, as milliseconds since 1 Jan 1970, UTC
+    pub last_observation_timestamp: i64,
+    /// Session identifier of the stream
+    pub session_id: i32,
+    /// Stream identifier of the stream
+    pub stream_id: i32,
+    /// Channel of the stream
+    pub channel: String,
+    /// Source address the stream was received from
+    pub source: String,
+}
+
+/// Memory map the loss report in an Aeron directory
+pub fn map_loss_report(aeron_dir: &Path) -> Result<MappedBuffer> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(aeron_dir.join(LOSS_REPORT_FILE))?;
+    let mmap = unsafe { MmapOptions::default().map_mut(&file) }?;
+    // SAFETY: The loss report is written by the media driver, and is only ever
+    // accessed through `AtomicBuffer` operations.
+    Ok(unsafe { MappedBuffer::new(mmap) })
+}
+
+/// Read all entries in a loss report, in the order they were created.
+/// Returns the number of entries read.
+pub fn read<A, F>(buffer: &A, mut handler: F) -> Result<i32>
+where
+    A: AtomicBuffer,
+    F: FnMut(&LossEntry) -> (),
+{
+    use loss_report_descriptor::*;
+
+    let capacity = buffer.capacity();
+    let mut entries = 0;
+    let mut offset = 0;
+
+    while offset + CHANNEL_OFFSET + size_of::<i32>() as IndexT <= capacity {
+        let observation_count = buffer.get_i64_volatile(offset + OBSERVATION_COUNT_OFFSET)?;
+        if observation_count <= 0 {
+            break;
+        }
+
+        let channel_offset = offset + CHANNEL_OFFSET;
+        let (channel, channel_length) = get_string(buffer, channel_offset)?;
+        let source_offset =
+            channel_offset + align(size_of::<i32>() + channel_length, size_of::<i32>()) as IndexT;
+        let (source, source_length) = get_string(buffer, source_offset)?;
+
+        entries += 1;
+        handler(&LossEntry {
+            observation_count,
+            total_bytes_lost: buffer.get_i64_volatile(offset + TOTAL_BYTES_LOST_OFFSET)?,
+            first_observation_timestamp: buffer.get_i64(offset + FIRST_OBSERVATION_OFFSET)?,
+            last_observation_timestamp: buffer
+                .get_i64_volatile(offset + LAST_OBSERVATION_OFFSET)?,
+            session_id: buffer.get_i32(offset + SESSION_ID_OFFSET)?,
+            stream_id: buffer.get_i32(offset + STREAM_ID_OFFSET)?,
+            channel,
+            source,
+        });
+
+        let entry_length =
+            CHANNEL_OFFSET as usize + size_of::<i32>() * 2 + channel_length + source_length;
+        offset += align(entry_length, ENTRY_ALIGNMENT as usize) as IndexT;
+    }
+
+    Ok(entries)
+}
+
+// Read a length-prefixed string, returning both the string and its encoded length
+fn get_string<A>(buffer: &A, offset: IndexT) -> Result<(String, usize)>
+where
+    A: AtomicBuffer,
+{
+    let length = buffer.get_i32(offset)?;
+    if length < 0 {
+        return Err(AeronError::IllegalState("string length is negative"));
+    }
+
+    let start = offset + size_of::<i32>() as IndexT;
+    buffer.bounds_check(start, length)?;
+    let bytes = &buffer[start as usize..(start + length) as usize];
+    Ok((String::from_utf8_lossy(bytes).into_owned(), length as usize))
+}
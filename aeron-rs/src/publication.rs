@@ -0,0 +1,137 @@
+//! Publications sending messages on a channel and stream through the media driver
+use crate::channel_uri::ChannelUri;
+use crate::client_conductor::{
+    lock_conductor, ClientConductor, PendingOperation, PublicationReady,
+};
+use crate::util::{AeronError, Result};
+use std::sync::{Arc, Mutex};
+
+/// A publication added by this client. The publication is removed from the media
+/// driver when closed or dropped.
+///
+/// Publications on a multi-destination-cast channel (`aeron:udp?control-mode=manual`)
+/// can have destinations added and removed while they are open. Offering messages
+/// is not yet supported.
+pub struct Publication {
+    channel: String,
+    ready: PublicationReady,
+    conductor: Arc<Mutex<ClientConductor>>,
+    driver_timeout_ms: i64,
+    is_closed: bool,
+}
+
+impl Publication {
+    pub(crate) fn new(
+        channel: &str,
+        ready: PublicationReady,
+        conductor: Arc<Mutex<ClientConductor>>,
+        driver_timeout_ms: i64,
+    ) -> Self {
+        Publication {
+            channel: channel.to_string(),
+            ready,
+            conductor,
+            driver_timeout_ms,
+            is_closed: false,
+        }
+    }
+
+    /// Get the channel this publication was added for
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Get the stream identifier of this publication
+    pub fn stream_id(&self) -> i32 {
+        self.ready.stream_id
+    }
+
+    /// Get the session identifier the media driver assigned to this publication
+    pub fn session_id(&self) -> i32 {
+        self.ready.session_id
+    }
+
+    /// Get the registration identifier of this client's link to the publication. Each
+    /// call to `Aeron::add_publication` has its own, even for the same channel and stream.
+    pub fn registration_id(&self) -> i64 {
+        self.ready.registration_id
+    }
+
+    /// Get the registration identifier the media driver gave the publication when it
+    /// was first added
+    pub fn original_registration_id(&self) -> i64 {
+        self.ready.original_registration_id
+    }
+
+    /// Get the identifier of the counter limiting how far this publication may advance
+    pub fn position_limit_counter_id(&self) -> i32 {
+        self.ready.position_limit_counter_id
+    }
+
+    /// Get the identifier of the counter tracking the status of the send channel
+    pub fn channel_status_indicator_id(&self) -> i32 {
+        self.ready.channel_status_indicator_id
+    }
+
+    /// Get the name of the file holding this publication's log buffers
+    pub fn log_file_name(&self) -> &str {
+        &self.ready.log_file_name
+    }
+
+    /// Ask the media driver to add a destination to this multi-destination-cast
+    /// publication. The returned operation completes once the media driver has
+    /// added the destination.
+    pub fn add_destination(&self, endpoint_channel: &str) -> Result<PendingOperation> {
+        self.send_destination(endpoint_channel, ClientConductor::add_destination)
+    }
+
+    /// Ask the media driver to remove a destination from this multi-destination-cast
+    /// publication. The returned operation completes once the media driver has
+    /// removed the destination.
+    pub fn remove_destination(&self, endpoint_channel: &str) -> Result<PendingOperation> {
+        self.send_destination(endpoint_channel, ClientConductor::remove_destination)
+    }
+
+    /// Check whether this publication has been closed
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Ask the media driver to remove this publication. Closing an already-closed
+    /// publication has no effect.
+    pub fn close(&mut self) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        self.is_closed = true;
+        lock_conductor(&self.conductor)?.remove_publication(self.ready.registration_id)
+    }
+
+    fn send_destination<F>(&self, endpoint_channel: &str, send: F) -> Result<PendingOperation>
+    where
+        F: FnOnce(&mut ClientConductor, i64, &str) -> Result<i64>,
+    {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("publication is closed"));
+        }
+        ChannelUri::parse(endpoint_channel)?;
+
+        let correlation_id = send(
+            &mut *lock_conductor(&self.conductor)?,
+            self.ready.original_registration_id,
+            endpoint_channel,
+        )?;
+        Ok(PendingOperation::new(
+            correlation_id,
+            self.conductor.clone(),
+            self.driver_timeout_ms,
+        ))
+    }
+}
+
+impl Drop for Publication {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
@@ -0,0 +1,449 @@
+//! Typed decoding of the counters maintained by the media driver. System counters
+//! describe the driver as a whole, while stream counters track positions and status
+//! for individual publications, subscriptions and channels.
+//!
+//! Stream counters share a key layout:
+//!
+//! ```text
+//!  0                   1                   2                   3
+//!  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                        Registration Id                        |
+//! |                                                               |
+//! +---------------------------------------------------------------+
+//! |                          Session Id                           |
+//! +---------------------------------------------------------------+
+//! |                           Stream Id                           |
+//! +---------------------------------------------------------------+
+//! |                        Channel Length                         |
+//! +---------------------------------------------------------------+
+//! |                       Channel (ASCII)                        ...
+//!...                                                              |
+//! +---------------------------------------------------------------+
+//! ```
+//!
+//! Channel status counters only hold the channel length and channel in their key.
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::AtomicBuffer;
+use crate::util::Result;
+use aeron_driver_sys::aeron_system_counter_enum_stct::*;
+use aeron_driver_sys::*;
+use std::convert::TryFrom;
+use std::mem::size_of;
+
+/// Type identifier shared by all system counters. The key of a system counter holds
+/// its `SystemCounter` identifier.
+pub const SYSTEM_COUNTER_TYPE_ID: i32 = AERON_SYSTEM_COUNTER_TYPE_ID as i32;
+
+/// Offset of the registration identifier in a stream counter key
+pub const REGISTRATION_ID_OFFSET: usize = 0;
+
+/// Offset of the session identifier in a stream counter key
+pub const SESSION_ID_OFFSET: usize = REGISTRATION_ID_OFFSET + size_of::<i64>();
+
+/// Offset of the stream identifier in a stream counter key
+pub const STREAM_ID_OFFSET: usize = SESSION_ID_OFFSET + size_of::<i32>();
+
+/// Offset of the channel length in a stream counter key
+pub const CHANNEL_OFFSET: usize = STREAM_ID_OFFSET + size_of::<i32>();
+
+define_enum!(
+    #[doc = "Counters maintained by the media driver for its own operation"]
+    #[derive(Clone, Copy)]
+    pub enum SystemCounter {
+        #[doc = "Bytes sent"]
+        BytesSent = AERON_SYSTEM_COUNTER_BYTES_SENT,
+        #[doc = "Bytes received"]
+        BytesReceived = AERON_SYSTEM_COUNTER_BYTES_RECEIVED,
+        #[doc = "Failed offers to the receiver proxy"]
+        ReceiverProxyFails = AERON_SYSTEM_COUNTER_RECEIVER_PROXY_FAILS,
+        #[doc = "Failed offers to the sender proxy"]
+        SenderProxyFails = AERON_SYSTEM_COUNTER_SENDER_PROXY_FAILS,
+        #[doc = "Failed offers to the driver conductor proxy"]
+        ConductorProxyFails = AERON_SYSTEM_COUNTER_CONDUCTOR_PROXY_FAILS,
+        #[doc = "NAKs sent"]
+        NakMessagesSent = AERON_SYSTEM_COUNTER_NAK_MESSAGES_SENT,
+        #[doc = "NAKs received"]
+        NakMessagesReceived = AERON_SYSTEM_COUNTER_NAK_MESSAGES_RECEIVED,
+        #[doc = "Status messages sent"]
+        StatusMessagesSent = AERON_SYSTEM_COUNTER_STATUS_MESSAGES_SENT,
+        #[doc = "Status messages received"]
+        StatusMessagesReceived = AERON_SYSTEM_COUNTER_STATUS_MESSAGES_RECEIVED,
+        #[doc = "Heartbeats sent"]
+        HeartbeatsSent = AERON_SYSTEM_COUNTER_HEARTBEATS_SENT,
+        #[doc = "Heartbeats received"]
+        HeartbeatsReceived = AERON_SYSTEM_COUNTER_HEARTBEATS_RECEIVED,
+        #[doc = "Retransmits sent"]
+        RetransmitsSent = AERON_SYSTEM_COUNTER_RETRANSMITS_SENT,
+        #[doc = "Flow control under runs"]
+        FlowControlUnderRuns = AERON_SYSTEM_COUNTER_FLOW_CONTROL_UNDER_RUNS,
+        #[doc = "Flow control over runs"]
+        FlowControlOverRuns = AERON_SYSTEM_COUNTER_FLOW_CONTROL_OVER_RUNS,
+        #[doc = "Invalid packets"]
+        InvalidPackets = AERON_SYSTEM_COUNTER_INVALID_PACKETS,
+        #[doc = "Errors"]
+        Errors = AERON_SYSTEM_COUNTER_ERRORS,
+        #[doc = "Short sends"]
+        ShortSends = AERON_SYSTEM_COUNTER_SHORT_SENDS,
+        #[doc = "Failed attempts to free log buffers"]
+        FreeFails = AERON_SYSTEM_COUNTER_FREE_FAILS,
+        #[doc = "Sender flow control limits applied"]
+        SenderFlowControlLimits = AERON_SYSTEM_COUNTER_SENDER_FLOW_CONTROL_LIMITS,
+        #[doc = "Unblocked publications"]
+        UnblockedPublications = AERON_SYSTEM_COUNTER_UNBLOCKED_PUBLICATIONS,
+        #[doc = "Unblocked control commands"]
+        UnblockedCommands = AERON_SYSTEM_COUNTER_UNBLOCKED_COMMANDS,
+        #[doc = "Possible TTL asymmetry"]
+        PossibleTtlAsymmetry = AERON_SYSTEM_COUNTER_POSSIBLE_TTL_ASYMMETRY,
+        #[doc = "Controllable idle strategy status"]
+        ControllableIdleStrategy = AERON_SYSTEM_COUNTER_CONTROLLABLE_IDLE_STRATEGY,
+        #[doc = "Loss gap fills"]
+        LossGapFills = AERON_SYSTEM_COUNTER_LOSS_GAP_FILLS,
+        #[doc = "Client liveness timeouts"]
+        ClientTimeouts = AERON_SYSTEM_COUNTER_CLIENT_TIMEOUTS,
+    }
+);
+
+impl SystemCounter {
+    /// Get the label the media driver uses for this counter
+    pub fn label(self) -> &'static str {
+        match self {
+            SystemCounter::BytesSent => "Bytes sent",
+            SystemCounter::BytesReceived => "Bytes received",
+            SystemCounter::ReceiverProxyFails => "Failed offers to ReceiverProxy",
+            SystemCounter::SenderProxyFails => "Failed offers to SenderProxy",
+            SystemCounter::ConductorProxyFails => "Failed offers to DriverConductorProxy",
+            SystemCounter::NakMessagesSent => "NAKs sent",
+            SystemCounter::NakMessagesReceived => "NAKs received",
+            SystemCounter::StatusMessagesSent => "Status Messages sent",
+            SystemCounter::StatusMessagesReceived => "Status Messages received",
+            SystemCounter::HeartbeatsSent => "Heartbeats sent",
+            SystemCounter::HeartbeatsReceived => "Heartbeats received",
+            SystemCounter::RetransmitsSent => "Retransmits sent",
+            SystemCounter::FlowControlUnderRuns => "Flow control under runs",
+            SystemCounter::FlowControlOverRuns => "Flow control over runs",
+            SystemCounter::InvalidPackets => "Invalid packets",
+            SystemCounter::Errors => "Errors",
+            SystemCounter::ShortSends => "Short sends",
+            SystemCounter::FreeFails => "Failed attempts to free log buffers",
+            SystemCounter::SenderFlowControlLimits => "Sender flow control limits applied",
+            SystemCounter::UnblockedPublications => "Unblocked Publications",
+            SystemCounter::UnblockedCommands => "Unblocked Control Commands",
+            SystemCounter::PossibleTtlAsymmetry => "Possible TTL Asymmetry",
+            SystemCounter::ControllableIdleStrategy => "ControllableIdleStrategy status",
+            SystemCounter::LossGapFills => "Loss gap fills",
+            SystemCounter::ClientTimeouts => "Client liveness timeouts",
+        }
+    }
+}
+
+define_enum!(
+    #[doc = "Types of counters that track a position within a single stream"]
+    #[derive(Clone, Copy)]
+    pub enum StreamCounterType {
+        #[doc = "Limit a publisher may write up to before being back-pressured"]
+        PublisherLimit = AERON_COUNTER_PUBLISHER_LIMIT_TYPE_ID,
+        #[doc = "Position the sender has transmitted up to"]
+        SenderPosition = AERON_COUNTER_SENDER_POSITION_TYPE_ID,
+        #[doc = "Highest position observed by the receiver"]
+        ReceiverHwm = AERON_COUNTER_RECEIVER_HWM_TYPE_ID,
+        #[doc = "Position a subscriber has consumed up to"]
+        SubscriberPosition = AERON_COUNTER_SUBSCRIPTION_POSITION_TYPE_ID,
+        #[doc = "Position the receiver has rebuilt up to without gaps"]
+        ReceiverPosition = AERON_COUNTER_RECEIVER_POSITION_TYPE_ID,
+        #[doc = "Limit the sender may transmit up to, as set by flow control"]
+        SenderLimit = AERON_COUNTER_SENDER_LIMIT_TYPE_ID,
+    }
+);
+
+define_enum!(
+    #[doc = "Types of counters that track the status of a channel endpoint"]
+    #[derive(Clone, Copy)]
+    pub enum ChannelStatusType {
+        #[doc = "Status of a channel used for sending"]
+        Send = AERON_COUNTER_SEND_CHANNEL_STATUS_TYPE_ID,
+        #[doc = "Status of a channel used for receiving"]
+        Receive = AERON_COUNTER_RECEIVE_CHANNEL_STATUS_TYPE_ID,
+    }
+);
+
+/// Channel endpoint is still being set up
+pub const CHANNEL_STATUS_INITIALIZING: i64 = 0;
+
+/// Channel endpoint could not be set up; check the error log for the cause
+pub const CHANNEL_STATUS_ERRORED: i64 = -1;
+
+/// Channel endpoint is active
+pub const CHANNEL_STATUS_ACTIVE: i64 = 1;
+
+/// Channel endpoint is being closed
+pub const CHANNEL_STATUS_CLOSING: i64 = 2;
+
+/// Current value of a system counter
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemCounterValue {
+    /// Which system counter this is
+    pub counter: SystemCounter,
+    /// Identifier of the counter within the counters buffers
+    pub counter_id: i32,
+    /// Current counter value
+    pub value: i64,
+}
+
+/// Current value of a stream position counter, along with the stream it belongs to
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamCounter {
+    /// Type of position tracked by this counter
+    pub counter_type: StreamCounterType,
+    /// Identifier of the counter within the counters buffers
+    pub counter_id: i32,
+    /// Registration identifier of the publication or subscription
+    pub registration_id: i64,
+    /// Session identifier of the stream
+    pub session_id: i32,
+    /// Stream identifier of the stream
+    pub stream_id: i32,
+    /// Channel of the stream. May be truncated if too long to fit in the counter key.
+    pub channel: String,
+    /// Current counter value
+    pub value: i64,
+}
+
+/// Current status of a channel endpoint
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelStatusCounter {
+    /// Whether this channel is used for sending or receiving
+    pub counter_type: ChannelStatusType,
+    /// Identifier of the counter within the counters buffers
+    pub counter_id: i32,
+    /// Channel of the endpoint. May be truncated if too long to fit in the counter key.
+    pub channel: String,
+    /// Current status; one of the `CHANNEL_STATUS_*` values
+    pub status: i64,
+}
+
+/// Read the current values of all system counters
+pub fn system_counters<A>(reader: &CountersReader<A>) -> Result<Vec<SystemCounterValue>>
+where
+    A: AtomicBuffer,
+{
+    let mut counters = Vec::new();
+    reader.for_each(|counter_id, type_id, key, _label| {
+        if type_id != SYSTEM_COUNTER_TYPE_ID {
+            return;
+        }
+
+        if let Ok(counter) = SystemCounter::try_from(get_i32(key, 0) as u32) {
+            counters.push(SystemCounterValue {
+                counter,
+                counter_id,
+                value: 0,
+            });
+        }
+    })?;
+
+    for counter in counters.iter_mut() {
+        counter.value = reader.counter_value(counter.counter_id)?;
+    }
+    Ok(counters)
+}
+
+/// Read the current values of all stream position counters
+pub fn stream_counters<A>(reader: &CountersReader<A>) -> Result<Vec<StreamCounter>>
+where
+    A: AtomicBuffer,
+{
+    let mut counters = Vec::new();
+    reader.for_each(|counter_id, type_id, key, _label| {
+        if let Ok(counter_type) = StreamCounterType::try_from(type_id as u32) {
+            counters.push(StreamCounter {
+                counter_type,
+                counter_id,
+                registration_id: get_i64(key, REGISTRATION_ID_OFFSET),
+                session_id: get_i32(key, SESSION_ID_OFFSET),
+                stream_id: get_i32(key, STREAM_ID_OFFSET),
+                channel: get_string(key, CHANNEL_OFFSET),
+                value: 0,
+            });
+        }
+    })?;
+
+    for counter in counters.iter_mut() {
+        counter.value = reader.counter_value(counter.counter_id)?;
+    }
+    Ok(counters)
+}
+
+/// Read the current status of all channel endpoints
+pub fn channel_status_counters<A>(reader: &CountersReader<A>) -> Result<Vec<ChannelStatusCounter>>
+where
+    A: AtomicBuffer,
+{
+    let mut counters = Vec::new();
+    reader.for_each(|counter_id, type_id, key, _label| {
+        if let Ok(counter_type) = ChannelStatusType::try_from(type_id as u32) {
+            counters.push(ChannelStatusCounter {
+                counter_type,
+                counter_id,
+                channel: get_string(key, 0),
+                status: 0,
+            });
+        }
+    })?;
+
+    for counter in counters.iter_mut() {
+        counter.status = reader.counter_value(counter.counter_id)?;
+    }
+    Ok(counters)
+}
+
+// Counter keys are always `MAX_KEY_LENGTH` long, so reads at the fixed offsets above
+// are known to be in bounds.
+fn get_i32(key: &[u8], offset: usize) -> i32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&key[offset..offset + 4]);
+    i32::from_le_bytes(bytes)
+}
+
+fn get_i64(key: &[u8], offset: usize) -> i64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&key[offset..offset + 8]);
+    i64::from_le_bytes(bytes)
+}
+
+fn get_string(key: &[u8], offset: usize) -> String {
+    let start = offset + size_of::<i32>();
+    let length = get_i32(key, offset).max(0) as usize;
+    let end = (start + length).min(key.len());
+    String::from_utf8_lossy(&key[start..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::counters::{CountersManager, COUNTER_LENGTH, METADATA_LENGTH};
+    use crate::status::{
+        channel_status_counters, stream_counters, system_counters, ChannelStatusCounter,
+        ChannelStatusType, StreamCounter, StreamCounterType, SystemCounter, SystemCounterValue,
+        SYSTEM_COUNTER_TYPE_ID,
+    };
+    use std::convert::TryFrom;
+
+    fn stream_key(registration_id: i64, session_id: i32, stream_id: i32, channel: &str) -> Vec<u8> {
+        let mut key = Vec::new();
+        key.extend_from_slice(&registration_id.to_le_bytes());
+        key.extend_from_slice(&session_id.to_le_bytes());
+        key.extend_from_slice(&stream_id.to_le_bytes());
+        key.extend_from_slice(&(channel.len() as i32).to_le_bytes());
+        key.extend_from_slice(channel.as_bytes());
+        key
+    }
+
+    fn manager() -> CountersManager<Vec<u8>> {
+        CountersManager::new(
+            vec![0u8; METADATA_LENGTH as usize * 8],
+            vec![0u8; COUNTER_LENGTH as usize * 8],
+        )
+    }
+
+    #[test]
+    fn should_convert_system_counter_ids() {
+        for id in 0..25 {
+            let counter = SystemCounter::try_from(id).unwrap();
+            assert_eq!(counter as u32, id);
+        }
+        assert_eq!(SystemCounter::try_from(25), Err(()));
+        assert_eq!(
+            SystemCounter::try_from(SystemCounter::ClientTimeouts as u32),
+            Ok(SystemCounter::ClientTimeouts)
+        );
+    }
+
+    #[test]
+    fn should_decode_system_counters() {
+        let mut manager = manager();
+        let errors = SystemCounter::Errors;
+        let id = manager
+            .allocate(
+                SYSTEM_COUNTER_TYPE_ID,
+                &(errors as i32).to_le_bytes(),
+                errors.label(),
+            )
+            .unwrap();
+        manager.set_counter_value(id, 3).unwrap();
+        manager.allocate(1001, &[], "not a system counter").unwrap();
+
+        assert_eq!(
+            system_counters(&manager).unwrap(),
+            vec![SystemCounterValue {
+                counter: errors,
+                counter_id: id,
+                value: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_decode_stream_counters() {
+        let mut manager = manager();
+        let channel = "aeron:udp?endpoint=localhost:40123";
+        let id = manager
+            .allocate(
+                StreamCounterType::SubscriberPosition as i32,
+                &stream_key(12, 34, 56, channel),
+                "sub-pos",
+            )
+            .unwrap();
+        manager.set_counter_value(id, 1024).unwrap();
+
+        assert_eq!(
+            stream_counters(&manager).unwrap(),
+            vec![StreamCounter {
+                counter_type: StreamCounterType::SubscriberPosition,
+                counter_id: id,
+                registration_id: 12,
+                session_id: 34,
+                stream_id: 56,
+                channel: channel.to_string(),
+                value: 1024,
+            }]
+        );
+        assert!(channel_status_counters(&manager).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_decode_channel_status_counters() {
+        let mut manager = manager();
+        let channel = "aeron:ipc";
+        let mut key = (channel.len() as i32).to_le_bytes().to_vec();
+        key.extend_from_slice(channel.as_bytes());
+        let id = manager
+            .allocate(ChannelStatusType::Send as i32, &key, "snd-channel")
+            .unwrap();
+        manager.set_counter_value(id, 1).unwrap();
+
+        assert_eq!(
+            channel_status_counters(&manager).unwrap(),
+            vec![ChannelStatusCounter {
+                counter_type: ChannelStatusType::Send,
+                counter_id: id,
+                channel: channel.to_string(),
+                status: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_truncate_channel_longer_than_key() {
+        let mut manager = manager();
+        let channel = "x".repeat(200);
+        let mut key = stream_key(1, 2, 3, &channel);
+        key.truncate(112);
+        let id = manager
+            .allocate(StreamCounterType::PublisherLimit as i32, &key, "pub-lmt")
+            .unwrap();
+
+        let counters = stream_counters(&manager).unwrap();
+        assert_eq!(counters[0].counter_id, id);
+        assert_eq!(counters[0].channel, "x".repeat(112 - 20));
+    }
+}
@@ -0,0 +1,139 @@
+//! Subscriptions receiving messages on a channel and stream through the media driver
+use crate::channel_uri::ChannelUri;
+use crate::client_conductor::{
+    dispatch_notifications, lock_conductor, ClientConductor, PendingOperation,
+};
+use crate::image::Image;
+use crate::util::{AeronError, Result};
+use std::sync::{Arc, Mutex};
+
+/// A subscription added by this client. The subscription is removed from the media
+/// driver when closed or dropped.
+///
+/// Multi-destination subscriptions (`aeron:udp?control-mode=manual`) can have
+/// destinations added and removed while they are open, so one subscription can
+/// receive a stream from several sources. Polling for messages is not yet supported.
+pub struct Subscription {
+    channel: String,
+    stream_id: i32,
+    registration_id: i64,
+    channel_status_indicator_id: i32,
+    conductor: Arc<Mutex<ClientConductor>>,
+    driver_timeout_ms: i64,
+    is_closed: bool,
+}
+
+impl Subscription {
+    pub(crate) fn new(
+        channel: &str,
+        stream_id: i32,
+        registration_id: i64,
+        channel_status_indicator_id: i32,
+        conductor: Arc<Mutex<ClientConductor>>,
+        driver_timeout_ms: i64,
+    ) -> Self {
+        Subscription {
+            channel: channel.to_string(),
+            stream_id,
+            registration_id,
+            channel_status_indicator_id,
+            conductor,
+            driver_timeout_ms,
+            is_closed: false,
+        }
+    }
+
+    /// Get the channel this subscription was added for
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Get the stream identifier of this subscription
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    /// Get the registration identifier the media driver associated with this subscription
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    /// Get the identifier of the counter tracking the status of the receive channel
+    pub fn channel_status_indicator_id(&self) -> i32 {
+        self.channel_status_indicator_id
+    }
+
+    /// Get the images currently available to this subscription, one for each
+    /// publisher session being received
+    pub fn images(&self) -> Result<Vec<Image>> {
+        Ok(lock_conductor(&self.conductor)?.images(self.registration_id))
+    }
+
+    /// Get the number of images currently available to this subscription
+    pub fn image_count(&self) -> Result<usize> {
+        Ok(self.images()?.len())
+    }
+
+    /// Ask the media driver to add a destination to this multi-destination
+    /// subscription. The returned operation completes once the media driver has
+    /// added the destination.
+    pub fn add_destination(&self, endpoint_channel: &str) -> Result<PendingOperation> {
+        self.send_destination(endpoint_channel, ClientConductor::add_rcv_destination)
+    }
+
+    /// Ask the media driver to remove a destination from this multi-destination
+    /// subscription. The returned operation completes once the media driver has
+    /// removed the destination.
+    pub fn remove_destination(&self, endpoint_channel: &str) -> Result<PendingOperation> {
+        self.send_destination(endpoint_channel, ClientConductor::remove_rcv_destination)
+    }
+
+    /// Check whether this subscription has been closed
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Ask the media driver to remove this subscription. Closing an already-closed
+    /// subscription has no effect.
+    pub fn close(&mut self) -> Result<()> {
+        if self.is_closed {
+            return Ok(());
+        }
+
+        self.is_closed = true;
+        let (result, notifications) = {
+            let mut conductor = lock_conductor(&self.conductor)?;
+            let result = conductor.remove_subscription(self.registration_id);
+            (result, conductor.take_notifications())
+        };
+        dispatch_notifications(notifications);
+        result
+    }
+
+    fn send_destination<F>(&self, endpoint_channel: &str, send: F) -> Result<PendingOperation>
+    where
+        F: FnOnce(&mut ClientConductor, i64, &str) -> Result<i64>,
+    {
+        if self.is_closed {
+            return Err(AeronError::IllegalState("subscription is closed"));
+        }
+        ChannelUri::parse(endpoint_channel)?;
+
+        let correlation_id = send(
+            &mut *lock_conductor(&self.conductor)?,
+            self.registration_id,
+            endpoint_channel,
+        )?;
+        Ok(PendingOperation::new(
+            correlation_id,
+            self.conductor.clone(),
+            self.driver_timeout_ms,
+        ))
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
@@ -1,23 +1,165 @@
 //! Various utility and helper bits for the Aeron client. Predominantly helpful
 //! in mapping between concepts in the C++ API and Rust
 
+use crate::channel_uri::ChannelUriError;
+use crate::command::error_response::DriverErrorResponse;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
 /// Helper type to indicate indexing operations in Aeron, Synonymous with the
 /// Aeron C++ `index_t` type. Used to imitate the Java API.
 // QUESTION: Can this just be updated to be `usize` in Rust?
 pub type IndexT = i32;
 
 /// Error types from operations in the Aeron client. Synonymous with the exceptions
-/// generated by the C++ client.
-#[derive(Debug, PartialEq)]
+/// generated by the C++ client, with enough context to tell where they came from.
+#[derive(Clone, Debug, PartialEq)]
 pub enum AeronError {
     /// Indication that an argument provided is an illegal value
-    IllegalArgument,
+    IllegalArgument(&'static str),
     /// Indication that a memory access would exceed the allowable bounds
-    OutOfBounds,
+    OutOfBounds {
+        /// Offset the access started at
+        offset: IndexT,
+        /// Number of bytes the access needed
+        length: IndexT,
+        /// Capacity of the buffer being accessed
+        capacity: IndexT,
+    },
     /// Indication that a buffer operation could not complete because of space constraints
-    InsufficientCapacity,
+    InsufficientCapacity {
+        /// Amount of space the operation needed
+        required: IndexT,
+        /// Amount of space that was available
+        capacity: IndexT,
+    },
     /// Indication that we have reached an invalid state and can't continue processing
-    IllegalState,
+    IllegalState(&'static str),
+    /// Indication that a file used to communicate with the media driver couldn't be used
+    Io {
+        /// Kind of IO failure
+        kind: io::ErrorKind,
+        /// Description of the IO failure
+        message: String,
+    },
+    /// Error response sent by the media driver in reply to a client command
+    DriverError(DriverErrorResponse),
+    /// Error reported by the embedded C media driver
+    MediaDriver {
+        /// Error code set by the media driver
+        code: i32,
+        /// Description of the error from the media driver
+        message: String,
+    },
+    /// Indication that the media driver has stopped sending heartbeats
+    DriverTimeout {
+        /// Milliseconds since the last media driver heartbeat
+        elapsed_ms: i64,
+        /// Milliseconds allowed between media driver heartbeats
+        timeout_ms: i64,
+    },
+    /// Indication that the media driver didn't reply to a request in time
+    RequestTimeout {
+        /// Correlation identifier of the request
+        correlation_id: i64,
+        /// Milliseconds waited for a reply
+        timeout_ms: i64,
+    },
+    /// Indication that the CnC file was created by an incompatible media driver
+    CncVersionMismatch {
+        /// Semantic version the client understands
+        expected: i32,
+        /// Semantic version found in the CnC file
+        actual: i32,
+    },
+    /// Indication that a channel string is invalid
+    ChannelUri(ChannelUriError),
+}
+
+fn write_version(f: &mut fmt::Formatter, version: i32) -> fmt::Result {
+    write!(
+        f,
+        "{}.{}.{}",
+        (version >> 16) & 0xFF,
+        (version >> 8) & 0xFF,
+        version & 0xFF
+    )
+}
+
+impl fmt::Display for AeronError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AeronError::IllegalArgument(reason) => write!(f, "illegal argument: {}", reason),
+            AeronError::OutOfBounds {
+                offset,
+                length,
+                capacity,
+            } => write!(
+                f,
+                "access of {} bytes at offset {} is out of bounds for capacity {}",
+                length, offset, capacity
+            ),
+            AeronError::InsufficientCapacity { required, capacity } => write!(
+                f,
+                "insufficient capacity: required {} but only {} available",
+                required, capacity
+            ),
+            AeronError::IllegalState(reason) => write!(f, "illegal state: {}", reason),
+            AeronError::Io { kind, message } => write!(f, "IO error ({:?}): {}", kind, message),
+            AeronError::DriverError(response) => write!(f, "media driver error {}", response),
+            AeronError::MediaDriver { code, message } => {
+                write!(f, "media driver failed with code {}: {}", code, message)
+            }
+            AeronError::DriverTimeout {
+                elapsed_ms,
+                timeout_ms,
+            } => write!(
+                f,
+                "media driver inactive: no heartbeat for {}ms, timeout is {}ms",
+                elapsed_ms, timeout_ms
+            ),
+            AeronError::RequestTimeout {
+                correlation_id,
+                timeout_ms,
+            } => write!(
+                f,
+                "no reply from media driver to request {} within {}ms",
+                correlation_id, timeout_ms
+            ),
+            AeronError::CncVersionMismatch { expected, actual } => {
+                write!(f, "CnC version mismatch: expected ")?;
+                write_version(f, *expected)?;
+                write!(f, " but found ")?;
+                write_version(f, *actual)
+            }
+            AeronError::ChannelUri(error) => write!(f, "invalid channel: {}", error),
+        }
+    }
+}
+
+impl Error for AeronError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AeronError::ChannelUri(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AeronError {
+    fn from(error: io::Error) -> Self {
+        AeronError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<ChannelUriError> for AeronError {
+    fn from(error: ChannelUriError) -> Self {
+        AeronError::ChannelUri(error)
+    }
 }
 
 /// Result type for operations in the Aeron client
@@ -55,3 +197,68 @@ pub mod bit {
         (val + (alignment - 1)) & !(alignment - 1)
     }
 }
+
+/// Time-related utility functions
+pub mod time {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Get the current time as milliseconds since 1 Jan 1970, UTC. Timestamps
+    /// shared with the media driver use this format.
+    pub fn epoch_millis() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::error_response::DriverErrorResponse;
+    use crate::control_protocol::ErrorCode;
+    use crate::sematic_version_compose;
+    use crate::util::AeronError;
+
+    #[test]
+    fn should_describe_errors_with_context() {
+        assert_eq!(
+            AeronError::OutOfBounds {
+                offset: 60,
+                length: 8,
+                capacity: 64
+            }
+            .to_string(),
+            "access of 8 bytes at offset 60 is out of bounds for capacity 64"
+        );
+        assert_eq!(
+            AeronError::CncVersionMismatch {
+                expected: sematic_version_compose(0, 0, 16),
+                actual: sematic_version_compose(0, 1, 2)
+            }
+            .to_string(),
+            "CnC version mismatch: expected 0.0.16 but found 0.1.2"
+        );
+        assert_eq!(
+            AeronError::DriverError(DriverErrorResponse {
+                offending_command_correlation_id: 7,
+                error_code: ErrorCode::InvalidChannel,
+                error_message: "unknown media".to_string()
+            })
+            .to_string(),
+            "media driver error InvalidChannel for command 7: unknown media"
+        );
+    }
+
+    #[test]
+    fn should_convert_io_errors() {
+        let error: AeronError =
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no cnc.dat").into();
+        assert_eq!(
+            error,
+            AeronError::Io {
+                kind: std::io::ErrorKind::NotFound,
+                message: "no cnc.dat".to_string()
+            }
+        );
+    }
+}
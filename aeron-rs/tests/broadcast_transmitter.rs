@@ -0,0 +1,157 @@
+use aeron_rs::concurrent::broadcast::{
+    buffer_descriptor, record_descriptor, BroadcastReceiver, BroadcastTransmitter,
+};
+use aeron_rs::concurrent::AtomicBuffer;
+use aeron_rs::util::bit::align;
+use aeron_rs::util::IndexT;
+
+const CAPACITY: usize = 1024;
+const TOTAL_BUFFER_LENGTH: usize = CAPACITY + buffer_descriptor::TRAILER_LENGTH as usize;
+const MSG_TYPE_ID: i32 = 7;
+const TAIL_INTENT_COUNTER_INDEX: i32 =
+    CAPACITY as i32 + buffer_descriptor::TAIL_INTENT_COUNTER_OFFSET;
+const TAIL_COUNTER_INDEX: i32 = CAPACITY as i32 + buffer_descriptor::TAIL_COUNTER_OFFSET;
+const LATEST_COUNTER_INDEX: i32 = CAPACITY as i32 + buffer_descriptor::LATEST_COUNTER_OFFSET;
+
+#[test]
+fn should_calculate_capacity_for_buffer() {
+    let transmitter = BroadcastTransmitter::new(vec![0u8; TOTAL_BUFFER_LENGTH]).unwrap();
+    assert_eq!(transmitter.capacity(), CAPACITY as IndexT);
+}
+
+#[test]
+fn should_throw_exception_for_capacity_that_is_not_power_of_two() {
+    let bytes = vec![0u8; 777 + buffer_descriptor::TRAILER_LENGTH as usize];
+
+    assert!(BroadcastTransmitter::new(bytes).is_err());
+}
+
+#[test]
+fn should_throw_exception_when_max_message_length_exceeded() {
+    let mut transmitter = BroadcastTransmitter::new(vec![0u8; TOTAL_BUFFER_LENGTH]).unwrap();
+    let msg = vec![0u8; transmitter.max_msg_length() as usize + 1];
+
+    assert!(transmitter.transmit(MSG_TYPE_ID, &msg).is_err());
+}
+
+#[test]
+fn should_throw_exception_when_message_type_id_invalid() {
+    let mut transmitter = BroadcastTransmitter::new(vec![0u8; TOTAL_BUFFER_LENGTH]).unwrap();
+
+    assert!(transmitter.transmit(0, &[0u8; 8]).is_err());
+}
+
+#[test]
+fn should_transmit_into_empty_buffer() {
+    let length: i32 = 8;
+    let record_length: i32 = length + record_descriptor::HEADER_LENGTH;
+    let aligned_record_length: i32 = align(
+        record_length as usize,
+        record_descriptor::RECORD_ALIGNMENT as usize,
+    ) as i32;
+    let record_offset = 0;
+
+    let mut transmitter = BroadcastTransmitter::new(vec![0u8; TOTAL_BUFFER_LENGTH]).unwrap();
+    transmitter
+        .transmit(MSG_TYPE_ID, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .unwrap();
+
+    let buffer = &transmitter;
+    assert_eq!(
+        buffer.get_i64(TAIL_INTENT_COUNTER_INDEX),
+        Ok(aligned_record_length as i64)
+    );
+    assert_eq!(
+        buffer.get_i64(TAIL_COUNTER_INDEX),
+        Ok(aligned_record_length as i64)
+    );
+    assert_eq!(buffer.get_i64(LATEST_COUNTER_INDEX), Ok(0));
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(record_offset)),
+        Ok(record_length)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::type_offset(record_offset)),
+        Ok(MSG_TYPE_ID)
+    );
+    let msg_offset = record_descriptor::msg_offset(record_offset) as usize;
+    assert_eq!(
+        &buffer[msg_offset..msg_offset + length as usize],
+        &[1, 2, 3, 4, 5, 6, 7, 8]
+    );
+}
+
+#[test]
+fn should_transmit_with_padding_on_buffer_wrap() {
+    let length: i32 = 1000 / 8;
+    let record_length: i32 = length + record_descriptor::HEADER_LENGTH;
+    let aligned_record_length: i32 = align(
+        record_length as usize,
+        record_descriptor::RECORD_ALIGNMENT as usize,
+    ) as i32;
+    let tail = (CAPACITY - record_descriptor::HEADER_LENGTH as usize) as i64;
+    let record_offset = tail as i32;
+
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    buffer.put_i64(TAIL_COUNTER_INDEX, tail).unwrap();
+    buffer.put_i64(TAIL_INTENT_COUNTER_INDEX, tail).unwrap();
+
+    let mut transmitter = BroadcastTransmitter::new(buffer).unwrap();
+    transmitter
+        .transmit(MSG_TYPE_ID, &vec![0u8; length as usize])
+        .unwrap();
+
+    let buffer = &transmitter;
+    let padding_length = CAPACITY as i64 - tail;
+    let new_tail = tail + padding_length + aligned_record_length as i64;
+    assert_eq!(buffer.get_i64(TAIL_INTENT_COUNTER_INDEX), Ok(new_tail));
+    assert_eq!(buffer.get_i64(TAIL_COUNTER_INDEX), Ok(new_tail));
+    assert_eq!(
+        buffer.get_i64(LATEST_COUNTER_INDEX),
+        Ok(tail + padding_length)
+    );
+
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(record_offset)),
+        Ok(padding_length as i32)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::type_offset(record_offset)),
+        Ok(record_descriptor::PADDING_MSG_TYPE_ID)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(0)),
+        Ok(record_length)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::type_offset(0)),
+        Ok(MSG_TYPE_ID)
+    );
+}
+
+#[test]
+fn should_be_received_by_broadcast_receiver() {
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    // Transmitter and receiver need to share the same memory, which Rust isn't
+    // happy about outside of a memory-mapped file. Alias the buffer instead.
+    let transmitter_buffer =
+        unsafe { ::std::slice::from_raw_parts_mut(buffer.as_mut_ptr(), buffer.len()) };
+    let receiver_buffer =
+        unsafe { ::std::slice::from_raw_parts_mut(buffer.as_mut_ptr(), buffer.len()) };
+
+    let mut transmitter = BroadcastTransmitter::new(transmitter_buffer).unwrap();
+    let mut receiver = BroadcastReceiver::new(receiver_buffer).unwrap();
+
+    // Write enough messages to wrap the buffer several times, reading each as we go
+    for i in 0..200 {
+        let msg = vec![0u8; i % 32 + 1];
+        transmitter.transmit(MSG_TYPE_ID + i as i32, &msg).unwrap();
+
+        assert_eq!(receiver.receive_next(), Ok(true));
+        assert_eq!(receiver.msg_type_id(), Ok(MSG_TYPE_ID + i as i32));
+        assert_eq!(receiver.length(), Ok(msg.len() as i32));
+        assert!(receiver.validate());
+        assert_eq!(receiver.receive_next(), Ok(false));
+    }
+    assert_eq!(receiver.lapped_count(), 0);
+}
@@ -0,0 +1,441 @@
+use aeron_rs::client::Aeron;
+use aeron_rs::cnc_descriptor::{CNC_FILE, CNC_VERSION, META_DATA_LENGTH};
+use aeron_rs::command::counter_message::CounterMessageDefn;
+use aeron_rs::command::counter_update::CounterUpdateDefn;
+use aeron_rs::command::error_response::ErrorResponseDefn;
+use aeron_rs::command::flyweight::Flyweight;
+use aeron_rs::command::remove_message::RemoveMessageDefn;
+use aeron_rs::concurrent::broadcast::{self, BroadcastTransmitter};
+use aeron_rs::concurrent::counters::{
+    CountersManager, CountersReader, COUNTER_LENGTH, METADATA_LENGTH,
+};
+use aeron_rs::concurrent::idle_strategy::SleepingIdleStrategy;
+use aeron_rs::concurrent::ringbuffer::{self, ManyToOneRingBuffer};
+use aeron_rs::concurrent::{AtomicBuffer, MappedBuffer};
+use aeron_rs::context::ClientContext;
+use aeron_rs::control_protocol::{ClientCommand, DriverResponse, ErrorCode};
+use aeron_rs::counter::Counter;
+use aeron_rs::util::time::epoch_millis;
+use aeron_rs::util::{AeronError, IndexT};
+use memmap::MmapOptions;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tempfile::tempdir;
+
+const TO_DRIVER_LENGTH: IndexT = 1024 + ringbuffer::buffer_descriptor::TRAILER_LENGTH;
+const TO_CLIENTS_LENGTH: IndexT = 1024 + broadcast::buffer_descriptor::TRAILER_LENGTH;
+const NUM_COUNTERS: IndexT = 8;
+const COUNTER_TYPE_ID: i32 = 1001;
+
+fn create_cnc(dir: &Path) -> MappedBuffer {
+    let cnc_length = META_DATA_LENGTH as IndexT
+        + TO_DRIVER_LENGTH
+        + TO_CLIENTS_LENGTH
+        + NUM_COUNTERS * (METADATA_LENGTH + COUNTER_LENGTH);
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dir.join(CNC_FILE))
+        .unwrap();
+    file.set_len(cnc_length as u64).unwrap();
+
+    // SAFETY: Test clients and simulated drivers only access the CnC file through
+    // `AtomicBuffer` operations, the same as a real media driver.
+    let mut cnc = unsafe { MappedBuffer::new(MmapOptions::default().map_mut(&file).unwrap()) };
+    cnc.put_i32(4, TO_DRIVER_LENGTH).unwrap();
+    cnc.put_i32(8, TO_CLIENTS_LENGTH).unwrap();
+    cnc.put_i32(12, NUM_COUNTERS * METADATA_LENGTH).unwrap();
+    cnc.put_i32(16, NUM_COUNTERS * COUNTER_LENGTH).unwrap();
+    cnc.put_i32_ordered(0, CNC_VERSION).unwrap();
+    cnc
+}
+
+/// Create a view of part of the CnC file
+fn view(cnc: &MappedBuffer, offset: IndexT, length: IndexT) -> MappedBuffer {
+    // SAFETY: See `create_cnc`
+    unsafe { cnc.view(offset, length) }.unwrap()
+}
+
+/// Answer counter commands the same way a media driver would, until told to stop.
+fn simulated_driver(cnc: MappedBuffer, running: Arc<AtomicBool>, removed: Arc<AtomicI64>) {
+    let to_clients_offset = META_DATA_LENGTH as IndexT + TO_DRIVER_LENGTH;
+    let counters_offset = to_clients_offset + TO_CLIENTS_LENGTH;
+
+    let mut to_driver =
+        ManyToOneRingBuffer::new(view(&cnc, META_DATA_LENGTH as IndexT, TO_DRIVER_LENGTH)).unwrap();
+    let mut to_clients =
+        BroadcastTransmitter::new(view(&cnc, to_clients_offset, TO_CLIENTS_LENGTH)).unwrap();
+    let mut counters = CountersManager::new(
+        view(&cnc, counters_offset, NUM_COUNTERS * METADATA_LENGTH),
+        view(
+            &cnc,
+            counters_offset + NUM_COUNTERS * METADATA_LENGTH,
+            NUM_COUNTERS * COUNTER_LENGTH,
+        ),
+    );
+    let mut registrations = HashMap::new();
+
+    while running.load(Ordering::SeqCst) {
+        to_driver.set_consumer_heartbeat_time(epoch_millis());
+
+        let mut responses = Vec::new();
+        to_driver
+            .read(
+                |msg_type_id, msg| match ClientCommand::try_from(msg_type_id as u32) {
+                    Ok(ClientCommand::AddCounter) => {
+                        let command =
+                            Flyweight::new::<CounterMessageDefn>(msg.to_vec(), 0).unwrap();
+                        let label = String::from_utf8(command.label().unwrap().to_vec()).unwrap();
+                        match counters.allocate(
+                            command.type_id(),
+                            command.key_buffer().unwrap(),
+                            &label,
+                        ) {
+                            Ok(counter_id) => {
+                                registrations.insert(command.correlation_id(), counter_id);
+                                responses.push(counter_update(
+                                    DriverResponse::OnCounterReady,
+                                    command.correlation_id(),
+                                    counter_id,
+                                ));
+                            }
+                            Err(_) => responses.push(error_response(
+                                command.correlation_id(),
+                                ErrorCode::GenericError,
+                                "unable to allocate counter",
+                            )),
+                        }
+                    }
+                    Ok(ClientCommand::RemoveCounter) => {
+                        let command = Flyweight::new::<RemoveMessageDefn>(msg.to_vec(), 0).unwrap();
+                        let registration_id = command.registration_id();
+                        let counter_id = registrations.remove(&registration_id).unwrap();
+                        counters.free(counter_id).unwrap();
+                        removed.store(registration_id, Ordering::SeqCst);
+                        responses.push(counter_update(
+                            DriverResponse::OnUnavailableCounter,
+                            registration_id,
+                            counter_id,
+                        ));
+                    }
+                    _ => (),
+                },
+            )
+            .unwrap();
+
+        for (response, msg) in responses {
+            to_clients.transmit(response as i32, &msg).unwrap();
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn counter_update(
+    response: DriverResponse,
+    correlation_id: i64,
+    counter_id: i32,
+) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 12];
+    Flyweight::new::<CounterUpdateDefn>(&mut msg[..], 0)
+        .unwrap()
+        .put_correlation_id(correlation_id)
+        .put_counter_id(counter_id);
+    (response, msg)
+}
+
+fn error_response(
+    correlation_id: i64,
+    code: ErrorCode,
+    message: &str,
+) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 16 + message.len()];
+    Flyweight::new::<ErrorResponseDefn>(&mut msg[..], 0)
+        .unwrap()
+        .put_offending_command_correlation_id(correlation_id)
+        .put_error_code(code as i32)
+        .put_error_message(message)
+        .unwrap();
+    (DriverResponse::OnError, msg)
+}
+
+#[test]
+fn should_add_and_remove_counter() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let driver = {
+        let (cnc, running, removed) = (
+            view(&cnc, 0, cnc.capacity()),
+            running.clone(),
+            removed.clone(),
+        );
+        thread::spawn(move || simulated_driver(cnc, running, removed))
+    };
+    // Wait for the first driver heartbeat
+    thread::sleep(Duration::from_millis(50));
+
+    let available = Arc::new(AtomicI64::new(0));
+    let context = {
+        let available = available.clone();
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_available_counter_handler(move |_reader, registration_id, _counter_id| {
+                available.store(registration_id, Ordering::SeqCst)
+            })
+    };
+    let aeron = Aeron::connect(context).unwrap();
+
+    let mut counter = aeron
+        .add_counter(COUNTER_TYPE_ID, &[1, 2, 3, 4], "test counter")
+        .unwrap();
+    assert_eq!(available.load(Ordering::SeqCst), counter.registration_id());
+
+    assert_eq!(counter.increment(), 0);
+    assert_eq!(counter.increment(), 1);
+    assert_eq!(counter.get(), 2);
+    counter.set_ordered(42);
+    assert_eq!(counter.get(), 42);
+
+    let counters_offset = META_DATA_LENGTH as IndexT + TO_DRIVER_LENGTH + TO_CLIENTS_LENGTH;
+    let reader = CountersReader::new(
+        view(&cnc, counters_offset, NUM_COUNTERS * METADATA_LENGTH),
+        view(
+            &cnc,
+            counters_offset + NUM_COUNTERS * METADATA_LENGTH,
+            NUM_COUNTERS * COUNTER_LENGTH,
+        ),
+    );
+    assert_eq!(reader.counter_type_id(counter.id()), Ok(COUNTER_TYPE_ID));
+    assert_eq!(
+        reader.counter_label(counter.id()),
+        Ok("test counter".to_string())
+    );
+    assert_eq!(reader.counter_value(counter.id()), Ok(42));
+
+    counter.close().unwrap();
+    assert!(counter.is_closed());
+    for _ in 0..100 {
+        if removed.load(Ordering::SeqCst) == counter.registration_id() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(removed.load(Ordering::SeqCst), counter.registration_id());
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_fail_add_counter_rejected_by_driver() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let driver = {
+        let (cnc, running, removed) = (
+            view(&cnc, 0, cnc.capacity()),
+            running.clone(),
+            removed.clone(),
+        );
+        thread::spawn(move || simulated_driver(cnc, running, removed))
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    let aeron = Aeron::connect(
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000),
+    )
+    .unwrap();
+
+    // Once every counter is allocated, the driver rejects the request
+    let _counters: Vec<_> = (0..NUM_COUNTERS)
+        .map(|_| aeron.add_counter(COUNTER_TYPE_ID, &[], "counter").unwrap())
+        .collect();
+    match aeron.add_counter(COUNTER_TYPE_ID, &[], "rejected") {
+        Err(AeronError::DriverError(response)) => {
+            assert_eq!(response.error_code, ErrorCode::GenericError);
+            assert_eq!(response.error_message, "unable to allocate counter");
+        }
+        other => panic!("Expected driver error, got {:?}", other.map(|c| c.id())),
+    }
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_fail_to_connect_without_driver_heartbeat() {
+    let temp_dir = tempdir().unwrap();
+    let _cnc = create_cnc(temp_dir.path());
+
+    let context = ClientContext::default().set_aeron_dir(temp_dir.path());
+    assert!(Aeron::connect(context).is_err());
+}
+
+fn wait_for<F>(mut condition: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    for _ in 0..200 {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    false
+}
+
+#[test]
+fn should_dispatch_unavailable_counter_from_conductor_thread() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let driver = {
+        let (cnc, running, removed) = (cnc, running.clone(), removed.clone());
+        thread::spawn(move || simulated_driver(cnc, running, removed))
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    let unavailable = Arc::new(AtomicI64::new(0));
+    let context = {
+        let unavailable = unavailable.clone();
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_idle_strategy(SleepingIdleStrategy::new(Duration::from_millis(1)))
+            .set_unavailable_counter_handler(move |_reader, registration_id, _counter_id| {
+                unavailable.store(registration_id, Ordering::SeqCst)
+            })
+    };
+    let mut aeron = Aeron::connect(context).unwrap();
+    assert!(aeron.conductor_invoker().is_none());
+
+    let mut counter = aeron.add_counter(COUNTER_TYPE_ID, &[], "counter").unwrap();
+    counter.close().unwrap();
+
+    // Nothing in this thread drives the conductor, so the notification must
+    // come from the conductor thread.
+    let registration_id = counter.registration_id();
+    assert!(wait_for(
+        || unavailable.load(Ordering::SeqCst) == registration_id
+    ));
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_only_dispatch_from_invoker_when_configured() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let driver = {
+        let (cnc, running, removed) = (cnc, running.clone(), removed.clone());
+        thread::spawn(move || simulated_driver(cnc, running, removed))
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    let unavailable = Arc::new(AtomicI64::new(0));
+    let context = {
+        let unavailable = unavailable.clone();
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_use_conductor_agent_invoker(true)
+            .set_unavailable_counter_handler(move |_reader, registration_id, _counter_id| {
+                unavailable.store(registration_id, Ordering::SeqCst)
+            })
+    };
+    let mut aeron = Aeron::connect(context).unwrap();
+
+    // First invocation sends a keepalive to the driver
+    assert!(aeron.conductor_invoker().unwrap().invoke().unwrap() > 0);
+
+    let mut counter = aeron.add_counter(COUNTER_TYPE_ID, &[], "counter").unwrap();
+    let registration_id = counter.registration_id();
+    counter.close().unwrap();
+
+    // Wait for the driver to remove the counter; without invoking, nobody sees it
+    assert!(wait_for(
+        || removed.load(Ordering::SeqCst) == registration_id
+    ));
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(unavailable.load(Ordering::SeqCst), 0);
+
+    let invoker = aeron.conductor_invoker().unwrap();
+    assert!(wait_for(|| {
+        invoker.invoke().unwrap();
+        unavailable.load(Ordering::SeqCst) == registration_id
+    }));
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_allow_counter_handlers_to_call_into_client() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let driver = {
+        let (cnc, running, removed) = (
+            view(&cnc, 0, cnc.capacity()),
+            running.clone(),
+            removed.clone(),
+        );
+        thread::spawn(move || simulated_driver(cnc, running, removed))
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    // Handlers run after the conductor lock is released, so adding a counter from
+    // inside one mustn't deadlock
+    let shared: Arc<Mutex<Option<Arc<Aeron>>>> = Arc::new(Mutex::new(None));
+    let nested: Arc<Mutex<Option<Counter>>> = Arc::new(Mutex::new(None));
+    let context = {
+        let (shared, nested) = (shared.clone(), nested.clone());
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_available_counter_handler(move |_reader, _registration_id, _counter_id| {
+                let aeron = shared.lock().unwrap().clone();
+                let mut nested = nested.lock().unwrap();
+                if let (Some(aeron), None) = (aeron, nested.as_ref()) {
+                    *nested = Some(aeron.add_counter(COUNTER_TYPE_ID, &[], "nested").unwrap());
+                }
+            })
+    };
+    let aeron = Arc::new(Aeron::connect(context).unwrap());
+    *shared.lock().unwrap() = Some(aeron.clone());
+
+    let mut counter = aeron.add_counter(COUNTER_TYPE_ID, &[], "outer").unwrap();
+    assert!(wait_for(|| nested.lock().unwrap().is_some()));
+    let mut nested = nested.lock().unwrap().take().unwrap();
+    assert_ne!(nested.id(), counter.id());
+
+    nested.close().unwrap();
+    counter.close().unwrap();
+    *shared.lock().unwrap() = None;
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
@@ -0,0 +1,343 @@
+mod common;
+
+use crate::common::{create_cnc, error_response, operation_succeeded, start_driver, wait_for};
+use aeron_rs::channel_uri::ChannelUriError;
+use aeron_rs::client::Aeron;
+use aeron_rs::command::destination_message::DestinationMessageDefn;
+use aeron_rs::command::flyweight::Flyweight;
+use aeron_rs::command::publication_buffers_ready::PublicationBuffersReadyDefn;
+use aeron_rs::command::publication_message::PublicationMessageDefn;
+use aeron_rs::command::remove_message::RemoveMessageDefn;
+use aeron_rs::concurrent::MappedBuffer;
+use aeron_rs::context::ClientContext;
+use aeron_rs::control_protocol::{ClientCommand, DriverResponse, ErrorCode};
+use aeron_rs::util::AeronError;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tempfile::tempdir;
+
+const MDC_CHANNEL: &str = "aeron:udp?control=localhost:40456|control-mode=manual";
+const STREAM_ID: i32 = 1001;
+const SESSION_ID: i32 = -7;
+
+/// Answer publication commands the same way a media driver would. Adding the same
+/// channel and stream again links to the existing publication, and each link is removed
+/// separately. Destinations currently added to each publication are recorded in
+/// `destinations`.
+fn publication_driver(
+    cnc: MappedBuffer,
+    running: Arc<AtomicBool>,
+    removed: Arc<AtomicI64>,
+    destinations: Arc<Mutex<HashSet<String>>>,
+) -> thread::JoinHandle<()> {
+    let mut publications = HashMap::new();
+    let mut links = HashMap::new();
+    start_driver(
+        cnc,
+        running,
+        move |command, msg, _counters, responses| match command {
+            ClientCommand::AddPublication => {
+                let command = Flyweight::new::<PublicationMessageDefn>(msg.to_vec(), 0).unwrap();
+                let channel = command.channel().unwrap().to_string();
+                let correlation_id = command.correlation_id();
+                if command.stream_id() < 0 {
+                    responses.push(error_response(
+                        correlation_id,
+                        ErrorCode::InvalidChannel,
+                        "invalid stream id",
+                    ));
+                    return;
+                }
+
+                let original_registration_id = publications
+                    .iter()
+                    .find(|&(_, added)| *added == (channel.clone(), command.stream_id()))
+                    .map(|(&registration_id, _)| registration_id)
+                    .unwrap_or(correlation_id);
+                let log_file_name = format!(
+                    "/dev/shm/aeron/publications/{}.logbuffer",
+                    original_registration_id
+                );
+                let mut ready = vec![0u8; 36 + log_file_name.len()];
+                Flyweight::new::<PublicationBuffersReadyDefn>(&mut ready[..], 0)
+                    .unwrap()
+                    .put_correlation_id(correlation_id)
+                    .put_registration_id(original_registration_id)
+                    .put_session_id(SESSION_ID)
+                    .put_stream_id(command.stream_id())
+                    .put_position_limit_counter_id(0)
+                    .put_channel_status_indicator_id(1)
+                    .put_log_file_name(&log_file_name)
+                    .unwrap();
+                publications.insert(original_registration_id, (channel, command.stream_id()));
+                links.insert(correlation_id, original_registration_id);
+                responses.push((DriverResponse::OnPublicationReady, ready));
+            }
+            ClientCommand::RemovePublication => {
+                let command = Flyweight::new::<RemoveMessageDefn>(msg.to_vec(), 0).unwrap();
+                let registration_id = command.registration_id();
+                let original_registration_id = match links.remove(&registration_id) {
+                    Some(original_registration_id) => original_registration_id,
+                    None => {
+                        responses.push(error_response(
+                            command.correlation_id(),
+                            ErrorCode::UnknownPublication,
+                            "unknown publication",
+                        ));
+                        return;
+                    }
+                };
+                if !links.values().any(|&id| id == original_registration_id) {
+                    publications.remove(&original_registration_id);
+                }
+                removed.store(registration_id, Ordering::SeqCst);
+                responses.push(operation_succeeded(command.correlation_id()));
+            }
+            ClientCommand::AddDestination | ClientCommand::RemoveDestination => {
+                let is_add = command == ClientCommand::AddDestination;
+                let command = Flyweight::new::<DestinationMessageDefn>(msg.to_vec(), 0).unwrap();
+                let correlation_id = command.correlation_id();
+                let channel = command.channel().unwrap().to_string();
+                let mut destinations = destinations.lock().unwrap();
+
+                let response = match publications.get(&command.registration_id()) {
+                    None => error_response(
+                        correlation_id,
+                        ErrorCode::UnknownPublication,
+                        "unknown publication",
+                    ),
+                    Some((publication, _)) if !publication.contains("control-mode=manual") => {
+                        error_response(
+                            correlation_id,
+                            ErrorCode::InvalidChannel,
+                            "channel does not allow manual control",
+                        )
+                    }
+                    Some(_) if is_add => {
+                        destinations.insert(channel);
+                        operation_succeeded(correlation_id)
+                    }
+                    Some(_) => {
+                        destinations.remove(&channel);
+                        operation_succeeded(correlation_id)
+                    }
+                };
+                responses.push(response);
+            }
+            _ => (),
+        },
+    )
+}
+
+fn connect(dir: &std::path::Path) -> Aeron {
+    Aeron::connect(
+        ClientContext::default()
+            .set_aeron_dir(dir)
+            .set_driver_timeout_ms(1000),
+    )
+    .unwrap()
+}
+
+#[test]
+fn should_add_and_remove_publication() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = publication_driver(cnc, running.clone(), removed.clone(), destinations);
+
+    let aeron = connect(temp_dir.path());
+    let mut publication = aeron.add_publication(MDC_CHANNEL, STREAM_ID).unwrap();
+    assert_eq!(publication.channel(), MDC_CHANNEL);
+    assert_eq!(publication.stream_id(), STREAM_ID);
+    assert_eq!(publication.session_id(), SESSION_ID);
+    assert_eq!(publication.position_limit_counter_id(), 0);
+    assert_eq!(publication.channel_status_indicator_id(), 1);
+    assert_eq!(
+        publication.log_file_name(),
+        format!(
+            "/dev/shm/aeron/publications/{}.logbuffer",
+            publication.original_registration_id()
+        )
+    );
+
+    publication.close().unwrap();
+    assert!(publication.is_closed());
+    let registration_id = publication.registration_id();
+    assert!(wait_for(
+        || removed.load(Ordering::SeqCst) == registration_id
+    ));
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_remove_each_link_to_shared_publication() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = publication_driver(cnc, running.clone(), removed.clone(), destinations);
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let context = {
+        let errors = errors.clone();
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_error_handler(move |error| errors.lock().unwrap().push(error))
+    };
+    let aeron = Aeron::connect(context).unwrap();
+
+    let mut first = aeron.add_publication(MDC_CHANNEL, STREAM_ID).unwrap();
+    let mut second = aeron.add_publication(MDC_CHANNEL, STREAM_ID).unwrap();
+    assert_eq!(
+        first.original_registration_id(),
+        second.original_registration_id()
+    );
+    assert_eq!(first.registration_id(), first.original_registration_id());
+    assert_ne!(first.registration_id(), second.registration_id());
+    assert_eq!(first.log_file_name(), second.log_file_name());
+
+    // Destinations apply to the shared publication
+    second
+        .add_destination("aeron:udp?endpoint=localhost:40457")
+        .unwrap()
+        .wait()
+        .unwrap();
+
+    second.close().unwrap();
+    let registration_id = second.registration_id();
+    assert!(wait_for(
+        || removed.load(Ordering::SeqCst) == registration_id
+    ));
+
+    first.close().unwrap();
+    let registration_id = first.registration_id();
+    assert!(wait_for(
+        || removed.load(Ordering::SeqCst) == registration_id
+    ));
+
+    thread::sleep(Duration::from_millis(20));
+    assert!(errors.lock().unwrap().is_empty());
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_reject_invalid_publication_channel() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = publication_driver(cnc, running.clone(), removed, destinations);
+
+    let aeron = connect(temp_dir.path());
+    match aeron.add_publication("aeron:tcp?endpoint=localhost:40123", STREAM_ID) {
+        Err(AeronError::ChannelUri(ChannelUriError::UnknownMedia(media))) => {
+            assert_eq!(media, "tcp")
+        }
+        other => panic!(
+            "Expected channel error, got {:?}",
+            other.map(|p| p.registration_id())
+        ),
+    }
+    match aeron.add_publication(MDC_CHANNEL, -1) {
+        Err(AeronError::DriverError(response)) => {
+            assert_eq!(response.error_code, ErrorCode::InvalidChannel)
+        }
+        other => panic!(
+            "Expected driver error, got {:?}",
+            other.map(|p| p.registration_id())
+        ),
+    }
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_add_and_remove_destinations() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = publication_driver(cnc, running.clone(), removed, destinations.clone());
+
+    let aeron = connect(temp_dir.path());
+    let publication = aeron.add_publication(MDC_CHANNEL, STREAM_ID).unwrap();
+
+    let first = "aeron:udp?endpoint=localhost:40457";
+    let second = "aeron:udp?endpoint=localhost:40458";
+    publication.add_destination(first).unwrap().wait().unwrap();
+    publication.add_destination(second).unwrap().wait().unwrap();
+    assert_eq!(destinations.lock().unwrap().len(), 2);
+
+    let mut operation = publication.remove_destination(first).unwrap();
+    assert!(operation.correlation_id() > publication.registration_id());
+    assert!(wait_for(|| operation.poll().unwrap()));
+    // Completed operations keep reporting their outcome
+    assert_eq!(operation.poll(), Ok(true));
+    assert!(!destinations.lock().unwrap().contains(first));
+    assert!(destinations.lock().unwrap().contains(second));
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_fail_destination_rejected_by_driver() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = publication_driver(cnc, running.clone(), removed, destinations.clone());
+
+    let aeron = connect(temp_dir.path());
+    let mut publication = aeron
+        .add_publication("aeron:udp?endpoint=localhost:40456", STREAM_ID)
+        .unwrap();
+
+    match publication
+        .add_destination("aeron:udp?endpoint=localhost:40457")
+        .unwrap()
+        .wait()
+    {
+        Err(AeronError::DriverError(response)) => {
+            assert_eq!(response.error_code, ErrorCode::InvalidChannel);
+            assert_eq!(
+                response.error_message,
+                "channel does not allow manual control"
+            );
+        }
+        other => panic!("Expected driver error, got {:?}", other),
+    }
+    assert!(destinations.lock().unwrap().is_empty());
+
+    assert!(publication.add_destination("localhost:40457").is_err());
+    publication.close().unwrap();
+    assert_eq!(
+        publication
+            .add_destination("aeron:udp?endpoint=localhost:40457")
+            .map(|operation| operation.correlation_id()),
+        Err(AeronError::IllegalState("publication is closed"))
+    );
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
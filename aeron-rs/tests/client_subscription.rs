@@ -0,0 +1,454 @@
+mod common;
+
+use crate::common::{create_cnc, error_response, operation_succeeded, start_driver, wait_for};
+use aeron_rs::client::Aeron;
+use aeron_rs::command::destination_message::DestinationMessageDefn;
+use aeron_rs::command::flyweight::Flyweight;
+use aeron_rs::command::image_buffers_ready::ImageBuffersReadyDefn;
+use aeron_rs::command::image_message::ImageMessageDefn;
+use aeron_rs::command::remove_message::RemoveMessageDefn;
+use aeron_rs::command::subscription_message::SubscriptionMessageDefn;
+use aeron_rs::command::subscription_ready::SubscriptionReadyDefn;
+use aeron_rs::concurrent::MappedBuffer;
+use aeron_rs::context::ClientContext;
+use aeron_rs::control_protocol::{ClientCommand, DriverResponse, ErrorCode};
+use aeron_rs::image::Image;
+use aeron_rs::subscription::Subscription;
+use aeron_rs::util::AeronError;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tempfile::tempdir;
+
+const MDS_CHANNEL: &str = "aeron:udp?control-mode=manual";
+const PRIMARY: &str = "aeron:udp?endpoint=localhost:40457";
+const BACKUP: &str = "aeron:udp?endpoint=localhost:40458";
+const STREAM_ID: i32 = 1001;
+const CHANNEL_STATUS_INDICATOR_ID: i32 = 3;
+const JOIN_POSITION: i64 = 4096;
+
+/// Answer subscription commands the same way a media driver would. Destinations
+/// currently added to each subscription are recorded in `destinations`, and each
+/// destination added to a subscription is received as a new image.
+fn subscription_driver(
+    cnc: MappedBuffer,
+    running: Arc<AtomicBool>,
+    removed: Arc<AtomicI64>,
+    destinations: Arc<Mutex<HashSet<String>>>,
+) -> thread::JoinHandle<()> {
+    let mut subscriptions = HashMap::new();
+    let mut images = HashMap::new();
+    let mut next_image_id = 1000;
+    start_driver(
+        cnc,
+        running,
+        move |command, msg, counters, responses| match command {
+            ClientCommand::AddSubscription => {
+                let command = Flyweight::new::<SubscriptionMessageDefn>(msg.to_vec(), 0).unwrap();
+                assert_eq!(command.registration_correlation_id(), -1);
+                let correlation_id = command.correlation_id();
+
+                let mut ready = vec![0u8; 12];
+                Flyweight::new::<SubscriptionReadyDefn>(&mut ready[..], 0)
+                    .unwrap()
+                    .put_correlation_id(correlation_id)
+                    .put_channel_status_indicator_id(CHANNEL_STATUS_INDICATOR_ID);
+                subscriptions.insert(correlation_id, command.channel().unwrap().to_string());
+                responses.push((DriverResponse::OnSubscriptionReady, ready));
+            }
+            ClientCommand::RemoveSubscription => {
+                let command = Flyweight::new::<RemoveMessageDefn>(msg.to_vec(), 0).unwrap();
+                subscriptions.remove(&command.registration_id());
+                removed.store(command.registration_id(), Ordering::SeqCst);
+                responses.push(operation_succeeded(command.correlation_id()));
+            }
+            ClientCommand::AddRcvDestination | ClientCommand::RemoveRcvDestination => {
+                let is_add = command == ClientCommand::AddRcvDestination;
+                let command = Flyweight::new::<DestinationMessageDefn>(msg.to_vec(), 0).unwrap();
+                let correlation_id = command.correlation_id();
+                let channel = command.channel().unwrap().to_string();
+                let mut destinations = destinations.lock().unwrap();
+
+                let response = match subscriptions.get(&command.registration_id()) {
+                    None => error_response(
+                        correlation_id,
+                        ErrorCode::UnknownSubscription,
+                        "unknown subscription",
+                    ),
+                    Some(subscription) if !subscription.contains("control-mode=manual") => {
+                        error_response(
+                            correlation_id,
+                            ErrorCode::InvalidChannel,
+                            "channel does not allow manual control",
+                        )
+                    }
+                    Some(_) if is_add => {
+                        let position_id = counters.allocate(0, &[], "sub-pos").unwrap();
+                        counters
+                            .set_counter_value(position_id, JOIN_POSITION)
+                            .unwrap();
+                        let image_id = next_image_id;
+                        next_image_id += 1;
+                        responses.push(available_image(
+                            image_id,
+                            command.registration_id(),
+                            position_id,
+                            &channel,
+                        ));
+                        images.insert(channel.clone(), (image_id, position_id));
+                        destinations.insert(channel);
+                        operation_succeeded(correlation_id)
+                    }
+                    Some(_) if destinations.remove(&channel) => {
+                        let (image_id, position_id) = images.remove(&channel).unwrap();
+                        counters.free(position_id).unwrap();
+                        responses.push(unavailable_image(
+                            image_id,
+                            command.registration_id(),
+                            &channel,
+                        ));
+                        operation_succeeded(correlation_id)
+                    }
+                    Some(_) => error_response(
+                        correlation_id,
+                        ErrorCode::GenericError,
+                        "unknown destination",
+                    ),
+                };
+                responses.push(response);
+            }
+            _ => (),
+        },
+    )
+}
+
+fn available_image(
+    correlation_id: i64,
+    subscription_registration_id: i64,
+    position_id: i32,
+    channel: &str,
+) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 256];
+    let length = {
+        let mut ready = Flyweight::new::<ImageBuffersReadyDefn>(&mut msg[..], 0).unwrap();
+        ready
+            .put_correlation_id(correlation_id)
+            .put_session_id(correlation_id as i32)
+            .put_stream_id(STREAM_ID)
+            .put_subscriber_registration_id(subscription_registration_id)
+            .put_subscriber_position_id(position_id);
+        ready
+            .put_log_file_name(&format!(
+                "/dev/shm/aeron/images/{}.logbuffer",
+                correlation_id
+            ))
+            .unwrap()
+            .put_source_identity(channel.trim_start_matches("aeron:udp?endpoint="))
+            .unwrap();
+        ready.length().unwrap()
+    };
+    msg.truncate(length as usize);
+    (DriverResponse::OnAvailableImage, msg)
+}
+
+fn unavailable_image(
+    correlation_id: i64,
+    subscription_registration_id: i64,
+    channel: &str,
+) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 24 + channel.len()];
+    Flyweight::new::<ImageMessageDefn>(&mut msg[..], 0)
+        .unwrap()
+        .put_correlation_id(correlation_id)
+        .put_subscription_registration_id(subscription_registration_id)
+        .put_stream_id(STREAM_ID)
+        .put_channel(channel)
+        .unwrap();
+    (DriverResponse::OnUnavailableImage, msg)
+}
+
+fn connect(dir: &std::path::Path) -> Aeron {
+    Aeron::connect(
+        ClientContext::default()
+            .set_aeron_dir(dir)
+            .set_driver_timeout_ms(1000),
+    )
+    .unwrap()
+}
+
+#[test]
+fn should_add_and_remove_subscription() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed.clone(), destinations);
+
+    let aeron = connect(temp_dir.path());
+    let mut subscription = aeron.add_subscription(MDS_CHANNEL, STREAM_ID).unwrap();
+    assert_eq!(subscription.channel(), MDS_CHANNEL);
+    assert_eq!(subscription.stream_id(), STREAM_ID);
+    assert_eq!(
+        subscription.channel_status_indicator_id(),
+        CHANNEL_STATUS_INDICATOR_ID
+    );
+
+    subscription.close().unwrap();
+    assert!(subscription.is_closed());
+    let registration_id = subscription.registration_id();
+    assert!(wait_for(
+        || removed.load(Ordering::SeqCst) == registration_id
+    ));
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_receive_from_primary_and_backup_destinations() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed, destinations.clone());
+
+    let aeron = connect(temp_dir.path());
+    let subscription = aeron.add_subscription(MDS_CHANNEL, STREAM_ID).unwrap();
+
+    subscription
+        .add_destination(PRIMARY)
+        .unwrap()
+        .wait()
+        .unwrap();
+    subscription
+        .add_destination(BACKUP)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert_eq!(destinations.lock().unwrap().len(), 2);
+
+    let mut operation = subscription.remove_destination(PRIMARY).unwrap();
+    assert!(wait_for(|| operation.poll().unwrap()));
+    assert!(!destinations.lock().unwrap().contains(PRIMARY));
+    assert!(destinations.lock().unwrap().contains(BACKUP));
+
+    match subscription.remove_destination(PRIMARY).unwrap().wait() {
+        Err(AeronError::DriverError(response)) => {
+            assert_eq!(response.error_message, "unknown destination")
+        }
+        other => panic!("Expected driver error, got {:?}", other),
+    }
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_fail_destination_without_manual_control() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed, destinations.clone());
+
+    let aeron = connect(temp_dir.path());
+    let mut subscription = aeron.add_subscription(PRIMARY, STREAM_ID).unwrap();
+
+    match subscription.add_destination(BACKUP).unwrap().wait() {
+        Err(AeronError::DriverError(response)) => {
+            assert_eq!(response.error_code, ErrorCode::InvalidChannel)
+        }
+        other => panic!("Expected driver error, got {:?}", other),
+    }
+    assert!(destinations.lock().unwrap().is_empty());
+
+    subscription.close().unwrap();
+    assert_eq!(
+        subscription
+            .add_destination(BACKUP)
+            .map(|operation| operation.correlation_id()),
+        Err(AeronError::IllegalState("subscription is closed"))
+    );
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_notify_image_handlers_from_context() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed, destinations);
+
+    let available = Arc::new(Mutex::new(Vec::new()));
+    let unavailable = Arc::new(Mutex::new(Vec::new()));
+    let context = {
+        let (available, unavailable) = (available.clone(), unavailable.clone());
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_available_image_handler(move |image| available.lock().unwrap().push(image.clone()))
+            .set_unavailable_image_handler(move |image| {
+                unavailable.lock().unwrap().push(image.clone())
+            })
+    };
+    let aeron = Aeron::connect(context).unwrap();
+    let subscription = aeron.add_subscription(MDS_CHANNEL, STREAM_ID).unwrap();
+
+    subscription
+        .add_destination(PRIMARY)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(wait_for(|| available.lock().unwrap().len() == 1));
+    let image: Image = available.lock().unwrap()[0].clone();
+    assert_eq!(
+        image.subscription_registration_id(),
+        subscription.registration_id()
+    );
+    assert_eq!(image.session_id(), image.correlation_id() as i32);
+    assert_eq!(image.stream_id(), STREAM_ID);
+    assert_eq!(image.source_identity(), "localhost:40457");
+    assert_eq!(image.join_position(), JOIN_POSITION);
+    assert_eq!(
+        image.log_file_name(),
+        format!("/dev/shm/aeron/images/{}.logbuffer", image.correlation_id())
+    );
+    assert_eq!(subscription.images().unwrap(), vec![image.clone()]);
+
+    subscription
+        .add_destination(BACKUP)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(wait_for(|| subscription.image_count().unwrap() == 2));
+
+    subscription
+        .remove_destination(PRIMARY)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(wait_for(|| unavailable.lock().unwrap().len() == 1));
+    assert_eq!(unavailable.lock().unwrap()[0], image);
+    let images = subscription.images().unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].source_identity(), "localhost:40458");
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_prefer_subscription_image_handlers() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed, destinations);
+
+    let context_images = Arc::new(AtomicI64::new(0));
+    let context = {
+        let (on_available, on_unavailable) = (context_images.clone(), context_images.clone());
+        ClientContext::default()
+            .set_aeron_dir(temp_dir.path())
+            .set_driver_timeout_ms(1000)
+            .set_available_image_handler(move |_image| {
+                on_available.fetch_add(1, Ordering::SeqCst);
+            })
+            .set_unavailable_image_handler(move |_image| {
+                on_unavailable.fetch_add(1, Ordering::SeqCst);
+            })
+    };
+    let aeron = Aeron::connect(context).unwrap();
+
+    let available = Arc::new(Mutex::new(Vec::new()));
+    let unavailable = Arc::new(Mutex::new(Vec::new()));
+    let mut subscription = {
+        let (available, unavailable) = (available.clone(), unavailable.clone());
+        aeron
+            .add_subscription_with_handlers(
+                MDS_CHANNEL,
+                STREAM_ID,
+                move |image| available.lock().unwrap().push(image.clone()),
+                move |image| unavailable.lock().unwrap().push(image.clone()),
+            )
+            .unwrap()
+    };
+
+    subscription
+        .add_destination(PRIMARY)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(wait_for(|| available.lock().unwrap().len() == 1));
+
+    // Images still available when the subscription closes are reported as unavailable
+    subscription.close().unwrap();
+    assert_eq!(*unavailable.lock().unwrap(), *available.lock().unwrap());
+    assert!(subscription.images().unwrap().is_empty());
+    assert_eq!(context_images.load(Ordering::SeqCst), 0);
+
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
+
+#[test]
+fn should_allow_image_handlers_to_call_into_client() {
+    let temp_dir = tempdir().unwrap();
+    let cnc = create_cnc(temp_dir.path());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let removed = Arc::new(AtomicI64::new(0));
+    let destinations = Arc::new(Mutex::new(HashSet::new()));
+    let driver = subscription_driver(cnc, running.clone(), removed, destinations);
+
+    // Handlers run after the conductor lock is released, so querying the
+    // subscription from inside one mustn't deadlock
+    let shared: Arc<Mutex<Option<Arc<Subscription>>>> = Arc::new(Mutex::new(None));
+    let image_counts = Arc::new(Mutex::new(Vec::new()));
+    let aeron = connect(temp_dir.path());
+    let subscription = {
+        let (shared, image_counts) = (shared.clone(), image_counts.clone());
+        aeron
+            .add_subscription_with_handlers(
+                MDS_CHANNEL,
+                STREAM_ID,
+                move |_image| {
+                    let subscription = shared.lock().unwrap().clone();
+                    if let Some(subscription) = subscription {
+                        let image_count = subscription.image_count().unwrap();
+                        image_counts.lock().unwrap().push(image_count);
+                    }
+                },
+                |_image| {},
+            )
+            .unwrap()
+    };
+    let subscription = Arc::new(subscription);
+    *shared.lock().unwrap() = Some(subscription.clone());
+
+    subscription
+        .add_destination(PRIMARY)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(wait_for(|| image_counts.lock().unwrap().len() == 1));
+    assert_eq!(*image_counts.lock().unwrap(), vec![1]);
+
+    *shared.lock().unwrap() = None;
+    running.store(false, Ordering::SeqCst);
+    driver.join().unwrap();
+}
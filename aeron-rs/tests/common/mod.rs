@@ -0,0 +1,172 @@
+//! Helpers shared by the client tests, which run against a simulated media driver
+#![allow(dead_code)]
+
+use aeron_rs::cnc_descriptor::{CNC_FILE, CNC_VERSION, META_DATA_LENGTH};
+use aeron_rs::command::error_response::ErrorResponseDefn;
+use aeron_rs::command::flyweight::Flyweight;
+use aeron_rs::command::operation_succeeded::OperationSucceededDefn;
+use aeron_rs::concurrent::broadcast::{self, BroadcastTransmitter};
+use aeron_rs::concurrent::counters::{
+    CountersManager, CountersReader, COUNTER_LENGTH, METADATA_LENGTH,
+};
+use aeron_rs::concurrent::ringbuffer::{self, ManyToOneRingBuffer};
+use aeron_rs::concurrent::{AtomicBuffer, MappedBuffer};
+use aeron_rs::control_protocol::{ClientCommand, DriverResponse, ErrorCode};
+use aeron_rs::util::time::epoch_millis;
+use aeron_rs::util::IndexT;
+use memmap::MmapOptions;
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const TO_DRIVER_LENGTH: IndexT = 1024 + ringbuffer::buffer_descriptor::TRAILER_LENGTH;
+pub const TO_CLIENTS_LENGTH: IndexT = 1024 + broadcast::buffer_descriptor::TRAILER_LENGTH;
+pub const NUM_COUNTERS: IndexT = 8;
+
+const TO_CLIENTS_OFFSET: IndexT = META_DATA_LENGTH as IndexT + TO_DRIVER_LENGTH;
+const COUNTERS_OFFSET: IndexT = TO_CLIENTS_OFFSET + TO_CLIENTS_LENGTH;
+
+/// Responses a simulated driver broadcasts to clients after handling a command
+pub type Responses = Vec<(DriverResponse, Vec<u8>)>;
+
+pub fn create_cnc(dir: &Path) -> MappedBuffer {
+    let cnc_length = META_DATA_LENGTH as IndexT
+        + TO_DRIVER_LENGTH
+        + TO_CLIENTS_LENGTH
+        + NUM_COUNTERS * (METADATA_LENGTH + COUNTER_LENGTH);
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dir.join(CNC_FILE))
+        .unwrap();
+    file.set_len(cnc_length as u64).unwrap();
+
+    // SAFETY: Test clients and simulated drivers only access the CnC file through
+    // `AtomicBuffer` operations, the same as a real media driver.
+    let mut cnc = unsafe { MappedBuffer::new(MmapOptions::default().map_mut(&file).unwrap()) };
+    cnc.put_i32(4, TO_DRIVER_LENGTH).unwrap();
+    cnc.put_i32(8, TO_CLIENTS_LENGTH).unwrap();
+    cnc.put_i32(12, NUM_COUNTERS * METADATA_LENGTH).unwrap();
+    cnc.put_i32(16, NUM_COUNTERS * COUNTER_LENGTH).unwrap();
+    cnc.put_i32_ordered(0, CNC_VERSION).unwrap();
+    cnc
+}
+
+/// Create a view of part of the CnC file
+pub fn view(cnc: &MappedBuffer, offset: IndexT, length: IndexT) -> MappedBuffer {
+    // SAFETY: See `create_cnc`
+    unsafe { cnc.view(offset, length) }.unwrap()
+}
+
+/// Create another view of the whole CnC file, to share with a simulated driver
+pub fn share(cnc: &MappedBuffer) -> MappedBuffer {
+    view(cnc, 0, cnc.capacity())
+}
+
+pub fn counters_reader(cnc: &MappedBuffer) -> CountersReader<MappedBuffer> {
+    CountersReader::new(
+        view(cnc, COUNTERS_OFFSET, NUM_COUNTERS * METADATA_LENGTH),
+        view(
+            cnc,
+            COUNTERS_OFFSET + NUM_COUNTERS * METADATA_LENGTH,
+            NUM_COUNTERS * COUNTER_LENGTH,
+        ),
+    )
+}
+
+/// Act as a media driver until told to stop: keep the heartbeat current, and pass each
+/// client command to `handler`, broadcasting whatever responses it produces.
+pub fn simulated_driver<F>(cnc: MappedBuffer, running: Arc<AtomicBool>, mut handler: F)
+where
+    F: FnMut(ClientCommand, &[u8], &mut CountersManager<MappedBuffer>, &mut Responses),
+{
+    let mut to_driver =
+        ManyToOneRingBuffer::new(view(&cnc, META_DATA_LENGTH as IndexT, TO_DRIVER_LENGTH)).unwrap();
+    let mut to_clients =
+        BroadcastTransmitter::new(view(&cnc, TO_CLIENTS_OFFSET, TO_CLIENTS_LENGTH)).unwrap();
+    let mut counters = CountersManager::new(
+        view(&cnc, COUNTERS_OFFSET, NUM_COUNTERS * METADATA_LENGTH),
+        view(
+            &cnc,
+            COUNTERS_OFFSET + NUM_COUNTERS * METADATA_LENGTH,
+            NUM_COUNTERS * COUNTER_LENGTH,
+        ),
+    );
+
+    while running.load(Ordering::SeqCst) {
+        to_driver.set_consumer_heartbeat_time(epoch_millis());
+
+        let mut responses = Vec::new();
+        to_driver
+            .read(|msg_type_id, msg| {
+                if let Ok(command) = ClientCommand::try_from(msg_type_id as u32) {
+                    handler(command, msg, &mut counters, &mut responses);
+                }
+            })
+            .unwrap();
+
+        for (response, msg) in responses {
+            to_clients.transmit(response as i32, &msg).unwrap();
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Start a simulated driver on its own thread, and wait for its first heartbeat
+pub fn start_driver<F>(
+    cnc: MappedBuffer,
+    running: Arc<AtomicBool>,
+    handler: F,
+) -> thread::JoinHandle<()>
+where
+    F: FnMut(ClientCommand, &[u8], &mut CountersManager<MappedBuffer>, &mut Responses)
+        + Send
+        + 'static,
+{
+    let driver = thread::spawn(move || simulated_driver(cnc, running, handler));
+    thread::sleep(Duration::from_millis(50));
+    driver
+}
+
+pub fn error_response(
+    correlation_id: i64,
+    code: ErrorCode,
+    message: &str,
+) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 16 + message.len()];
+    Flyweight::new::<ErrorResponseDefn>(&mut msg[..], 0)
+        .unwrap()
+        .put_offending_command_correlation_id(correlation_id)
+        .put_error_code(code as i32)
+        .put_error_message(message)
+        .unwrap();
+    (DriverResponse::OnError, msg)
+}
+
+pub fn operation_succeeded(correlation_id: i64) -> (DriverResponse, Vec<u8>) {
+    let mut msg = vec![0u8; 8];
+    Flyweight::new::<OperationSucceededDefn>(&mut msg[..], 0)
+        .unwrap()
+        .put_correlation_id(correlation_id);
+    (DriverResponse::OnOperationSuccess, msg)
+}
+
+pub fn wait_for<F>(mut condition: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    for _ in 0..200 {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    false
+}
@@ -0,0 +1,199 @@
+use aeron_rs::concurrent::broadcast::{
+    buffer_descriptor, record_descriptor, BroadcastReceiver, BroadcastTransmitter,
+    CopyBroadcastReceiver, DEFAULT_SCRATCH_LENGTH,
+};
+use aeron_rs::concurrent::AtomicBuffer;
+use aeron_rs::util::AeronError;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+const CAPACITY: usize = 1024;
+const TOTAL_BUFFER_LENGTH: usize = CAPACITY + buffer_descriptor::TRAILER_LENGTH as usize;
+const MSG_TYPE_ID: i32 = 7;
+
+// Transmitter and receiver need to share the same memory, which Rust isn't
+// happy about outside of a memory-mapped file. Alias the buffer instead.
+fn alias(buffer: &mut Vec<u8>) -> &'static mut [u8] {
+    unsafe { ::std::slice::from_raw_parts_mut(buffer.as_mut_ptr(), buffer.len()) }
+}
+
+#[test]
+fn should_copy_received_message() {
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+    let mut receiver =
+        CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap());
+
+    transmitter.transmit(MSG_TYPE_ID, &[1, 2, 3, 4]).unwrap();
+
+    let mut received = Vec::new();
+    let messages = receiver.receive(|msg_type_id, msg| {
+        assert_eq!(msg_type_id, MSG_TYPE_ID);
+        received.extend_from_slice(msg);
+    });
+
+    assert_eq!(messages, Ok(1));
+    assert_eq!(received, vec![1, 2, 3, 4]);
+    assert_eq!(receiver.receive(|_, _| {}), Ok(0));
+}
+
+#[test]
+fn should_fail_when_lapped_without_loss_handler() {
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+    let mut receiver =
+        CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap());
+
+    for _ in 0..CAPACITY / 8 {
+        transmitter.transmit(MSG_TYPE_ID, &[0u8; 24]).unwrap();
+    }
+
+    assert_eq!(
+        receiver.receive(|_, _| {}),
+        Err(AeronError::IllegalState(
+            "receiver was lapped by the transmitter"
+        ))
+    );
+}
+
+#[test]
+fn should_report_laps_and_resynchronize_with_loss_handler() {
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+
+    let laps = Arc::new(AtomicI64::new(0));
+    let handler_laps = laps.clone();
+    let mut receiver =
+        CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap())
+            .set_loss_handler(move |lapped| {
+                handler_laps.fetch_add(lapped, Ordering::SeqCst);
+            });
+
+    for i in 0..CAPACITY / 8 {
+        transmitter.transmit(MSG_TYPE_ID, &[i as u8; 24]).unwrap();
+    }
+
+    let mut received = Vec::new();
+    assert_eq!(
+        receiver.receive(|_, msg| received.extend_from_slice(msg)),
+        Ok(1)
+    );
+    assert_eq!(laps.load(Ordering::SeqCst), 1);
+    assert_eq!(receiver.lapped_count(), 1);
+
+    // Resynchronized to the most recent message
+    assert_eq!(received, vec![(CAPACITY / 8 - 1) as u8; 24]);
+    assert_eq!(receiver.receive(|_, _| {}), Ok(0));
+}
+
+#[test]
+fn should_grow_scratch_buffer_up_to_maximum() {
+    let capacity = DEFAULT_SCRATCH_LENGTH * 32;
+    let mut buffer = vec![0u8; capacity + buffer_descriptor::TRAILER_LENGTH as usize];
+    let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+    let mut receiver =
+        CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap())
+            .set_max_scratch_length(DEFAULT_SCRATCH_LENGTH * 2);
+
+    let msg = vec![3u8; DEFAULT_SCRATCH_LENGTH + 1];
+    transmitter.transmit(MSG_TYPE_ID, &msg).unwrap();
+
+    let mut received = Vec::new();
+    assert_eq!(
+        receiver.receive(|_, msg| received.extend_from_slice(msg)),
+        Ok(1)
+    );
+    assert_eq!(received, msg);
+
+    let msg = vec![3u8; DEFAULT_SCRATCH_LENGTH * 2 + 1];
+    transmitter.transmit(MSG_TYPE_ID, &msg).unwrap();
+    assert_eq!(
+        receiver.receive(|_, _| {}),
+        Err(AeronError::InsufficientCapacity {
+            required: msg.len() as i32,
+            capacity: (DEFAULT_SCRATCH_LENGTH * 2) as i32
+        })
+    );
+}
+
+#[test]
+fn should_skip_overwritten_record_with_invalid_length() {
+    // Record lengths giving a negative message length, and one far beyond the scratch maximum
+    for &record_length in &[record_descriptor::HEADER_LENGTH - 1, 0x7fff_0000] {
+        let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+        let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+
+        let laps = Arc::new(AtomicI64::new(0));
+        let handler_laps = laps.clone();
+        let mut receiver =
+            CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap())
+                .set_loss_handler(move |lapped| {
+                    handler_laps.fetch_add(lapped, Ordering::SeqCst);
+                });
+
+        transmitter.transmit(MSG_TYPE_ID, &[1, 2, 3, 4]).unwrap();
+
+        // Simulate the transmitter lapping the receiver and overwriting the latest
+        // record's header before the receiver reads it
+        let mut shared = alias(&mut buffer);
+        shared
+            .put_i64(
+                CAPACITY as i32 + buffer_descriptor::TAIL_INTENT_COUNTER_OFFSET,
+                CAPACITY as i64 * 2,
+            )
+            .unwrap();
+        shared
+            .put_i32(record_descriptor::length_offset(0), record_length)
+            .unwrap();
+
+        assert_eq!(
+            receiver.receive(|_, _| panic!("overwritten record was delivered")),
+            Ok(0)
+        );
+        assert_eq!(laps.load(Ordering::SeqCst), 1);
+
+        // Once the transmitter has rewritten the latest record, the receiver picks it up
+        // rather than following the overwritten length
+        shared
+            .put_i32(
+                record_descriptor::length_offset(0),
+                record_descriptor::HEADER_LENGTH + 4,
+            )
+            .unwrap();
+        shared
+            .put_i64(
+                CAPACITY as i32 + buffer_descriptor::TAIL_INTENT_COUNTER_OFFSET,
+                shared
+                    .get_i64(CAPACITY as i32 + buffer_descriptor::TAIL_COUNTER_OFFSET)
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let mut received = Vec::new();
+        assert_eq!(
+            receiver.receive(|_, msg| received.extend_from_slice(msg)),
+            Ok(1)
+        );
+        assert_eq!(received, vec![1, 2, 3, 4]);
+    }
+}
+
+#[test]
+fn should_reject_message_longer_than_max_scratch_length() {
+    let mut buffer = vec![0u8; TOTAL_BUFFER_LENGTH];
+    let mut transmitter = BroadcastTransmitter::new(alias(&mut buffer)).unwrap();
+    let mut receiver =
+        CopyBroadcastReceiver::new(BroadcastReceiver::new(alias(&mut buffer)).unwrap())
+            .set_max_scratch_length(64);
+
+    // Fits in the default scratch buffer, but not under the configured maximum
+    let msg = [5u8; 65];
+    transmitter.transmit(MSG_TYPE_ID, &msg).unwrap();
+    assert_eq!(
+        receiver.receive(|_, _| panic!("oversized message was delivered")),
+        Err(AeronError::InsufficientCapacity {
+            required: msg.len() as i32,
+            capacity: 64
+        })
+    );
+}
@@ -0,0 +1,82 @@
+use aeron_rs::concurrent::errors::error_log_descriptor::*;
+use aeron_rs::concurrent::errors::{error_log_reader, DistinctErrorLog};
+use aeron_rs::concurrent::AtomicBuffer;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+const CAPACITY: usize = 1024;
+
+fn read_all(buffer: &Vec<u8>) -> Vec<(i32, i64, i64, String)> {
+    let mut errors = Vec::new();
+    error_log_reader::read(
+        buffer,
+        |count, first, last, error| errors.push((count, first, last, error.to_string())),
+        0,
+    )
+    .unwrap();
+    errors
+}
+
+#[test]
+fn should_record_first_observation() {
+    let mut log = DistinctErrorLog::new(vec![0u8; CAPACITY]).set_epoch_clock(|| 7);
+
+    assert_eq!(log.record("error"), Ok(true));
+    assert_eq!(
+        log.buffer().get_i32(LENGTH_OFFSET),
+        Ok(HEADER_LENGTH + "error".len() as i32)
+    );
+    assert_eq!(read_all(log.buffer()), vec![(1, 7, 7, "error".to_string())]);
+}
+
+#[test]
+fn should_count_repeated_observations() {
+    let time = Arc::new(AtomicI64::new(10));
+    let clock_time = time.clone();
+    let mut log = DistinctErrorLog::new(vec![0u8; CAPACITY])
+        .set_epoch_clock(move || clock_time.load(Ordering::SeqCst));
+
+    log.record("error").unwrap();
+    time.store(20, Ordering::SeqCst);
+    log.record("error").unwrap();
+
+    assert_eq!(
+        read_all(log.buffer()),
+        vec![(2, 10, 20, "error".to_string())]
+    );
+}
+
+#[test]
+fn should_record_distinct_errors_separately() {
+    let mut log = DistinctErrorLog::new(vec![0u8; CAPACITY]).set_epoch_clock(|| 5);
+
+    log.record("first").unwrap();
+    log.record("second").unwrap();
+    log.record("first").unwrap();
+
+    assert_eq!(
+        read_all(log.buffer()),
+        vec![
+            (2, 5, 5, "first".to_string()),
+            (1, 5, 5, "second".to_string())
+        ]
+    );
+    // Records are aligned, so the second starts on the next 8-byte boundary
+    let second_offset = (HEADER_LENGTH + 5 + 7) & !7;
+    assert_eq!(
+        log.buffer()
+            .get_i32(second_offset + OBSERVATION_COUNT_OFFSET),
+        Ok(1)
+    );
+}
+
+#[test]
+fn should_fail_to_record_when_full() {
+    let mut log = DistinctErrorLog::new(vec![0u8; HEADER_LENGTH as usize + 8]);
+
+    assert_eq!(log.record("12345678"), Ok(true));
+    assert_eq!(log.record("another"), Ok(false));
+    // Existing errors can still be observed
+    assert_eq!(log.record("12345678"), Ok(true));
+    assert_eq!(read_all(log.buffer()).len(), 1);
+}
@@ -0,0 +1,121 @@
+use aeron_rs::concurrent::errors::error_log_descriptor::*;
+use aeron_rs::concurrent::errors::error_log_reader;
+use aeron_rs::concurrent::AtomicBuffer;
+use aeron_rs::util::bit::align;
+use aeron_rs::util::{AeronError, IndexT};
+
+const CAPACITY: usize = 1024;
+
+/// Write an error record the same way the media driver would, returning the
+/// offset of the next record.
+fn write_record(
+    buffer: &mut Vec<u8>,
+    offset: IndexT,
+    observation_count: i32,
+    first_timestamp: i64,
+    last_timestamp: i64,
+    error: &str,
+) -> IndexT {
+    let length = HEADER_LENGTH + error.len() as IndexT;
+    buffer
+        .put_slice(
+            offset + ENCODED_ERROR_OFFSET,
+            error.as_bytes(),
+            0,
+            error.len() as IndexT,
+        )
+        .unwrap();
+    buffer
+        .put_i64(offset + FIRST_OBSERVATION_TIMESTAMP_OFFSET, first_timestamp)
+        .unwrap();
+    buffer
+        .put_i64(offset + LAST_OBSERVATION_TIMESTAMP_OFFSET, last_timestamp)
+        .unwrap();
+    buffer
+        .put_i32(offset + OBSERVATION_COUNT_OFFSET, observation_count)
+        .unwrap();
+    buffer
+        .put_i32_ordered(offset + LENGTH_OFFSET, length)
+        .unwrap();
+
+    offset + align(length as usize, RECORD_ALIGNMENT as usize) as IndexT
+}
+
+#[test]
+fn should_read_no_errors_when_empty() {
+    let buffer = vec![0u8; CAPACITY];
+    let entries = error_log_reader::read(&buffer, |_, _, _, _| panic!("No errors"), 0);
+    assert_eq!(entries, Ok(0));
+}
+
+#[test]
+fn should_read_errors_in_order() {
+    let mut buffer = vec![0u8; CAPACITY];
+    let offset = write_record(&mut buffer, 0, 3, 10, 30, "first error");
+    write_record(&mut buffer, offset, 1, 20, 20, "second");
+
+    let mut errors = Vec::new();
+    let entries = error_log_reader::read(
+        &buffer,
+        |count, first, last, error| errors.push((count, first, last, error.to_string())),
+        0,
+    );
+
+    assert_eq!(entries, Ok(2));
+    assert_eq!(
+        errors,
+        vec![
+            (3, 10, 30, "first error".to_string()),
+            (1, 20, 20, "second".to_string())
+        ]
+    );
+}
+
+#[test]
+fn should_skip_errors_last_observed_before_timestamp() {
+    let mut buffer = vec![0u8; CAPACITY];
+    let offset = write_record(&mut buffer, 0, 3, 10, 30, "old error");
+    write_record(&mut buffer, offset, 1, 40, 40, "new error");
+
+    let mut errors = Vec::new();
+    let entries =
+        error_log_reader::read(&buffer, |_, _, _, error| errors.push(error.to_string()), 35);
+
+    assert_eq!(entries, Ok(1));
+    assert_eq!(errors, vec!["new error".to_string()]);
+}
+
+#[test]
+fn should_include_errors_last_observed_at_timestamp() {
+    let mut buffer = vec![0u8; CAPACITY];
+    write_record(&mut buffer, 0, 1, 10, 30, "error");
+
+    let entries = error_log_reader::read(&buffer, |_, _, _, _| {}, 30);
+    assert_eq!(entries, Ok(1));
+}
+
+#[test]
+fn should_read_error_filling_buffer() {
+    let mut buffer = vec![0u8; CAPACITY];
+    let error = "e".repeat(CAPACITY - HEADER_LENGTH as usize);
+    write_record(&mut buffer, 0, 1, 10, 10, &error);
+
+    let mut length = 0;
+    let entries = error_log_reader::read(&buffer, |_, _, _, e| length = e.len(), 0);
+    assert_eq!(entries, Ok(1));
+    assert_eq!(length, error.len());
+}
+
+#[test]
+fn should_reject_corrupt_record_length() {
+    let mut buffer = vec![0u8; CAPACITY];
+    buffer.put_i32(LENGTH_OFFSET, CAPACITY as i32 + 8).unwrap();
+
+    let entries = error_log_reader::read(&buffer, |_, _, _, _| {}, 0);
+    assert_eq!(
+        entries,
+        Err(AeronError::IllegalState(
+            "error log record length is corrupt"
+        ))
+    );
+}
@@ -0,0 +1,102 @@
+use aeron_rs::concurrent::AtomicBuffer;
+use aeron_rs::loss_report::loss_report_descriptor::*;
+use aeron_rs::loss_report::{self, LossEntry};
+use aeron_rs::util::bit::align;
+use aeron_rs::util::IndexT;
+
+const CAPACITY: usize = 1024;
+
+/// Write a loss entry the same way the media driver would, returning the
+/// offset of the next entry.
+fn write_entry(buffer: &mut Vec<u8>, offset: IndexT, entry: &LossEntry) -> IndexT {
+    let channel_offset = offset + CHANNEL_OFFSET;
+    let source_offset = channel_offset + align(4 + entry.channel.len(), 4) as IndexT;
+
+    buffer
+        .put_i64(offset + TOTAL_BYTES_LOST_OFFSET, entry.total_bytes_lost)
+        .unwrap();
+    buffer
+        .put_i64(
+            offset + FIRST_OBSERVATION_OFFSET,
+            entry.first_observation_timestamp,
+        )
+        .unwrap();
+    buffer
+        .put_i64(
+            offset + LAST_OBSERVATION_OFFSET,
+            entry.last_observation_timestamp,
+        )
+        .unwrap();
+    buffer
+        .put_i32(offset + SESSION_ID_OFFSET, entry.session_id)
+        .unwrap();
+    buffer
+        .put_i32(offset + STREAM_ID_OFFSET, entry.stream_id)
+        .unwrap();
+    for (string_offset, string) in &[
+        (channel_offset, &entry.channel),
+        (source_offset, &entry.source),
+    ] {
+        buffer.put_i32(*string_offset, string.len() as i32).unwrap();
+        buffer
+            .put_slice(
+                *string_offset + 4,
+                string.as_bytes(),
+                0,
+                string.len() as i32,
+            )
+            .unwrap();
+    }
+    buffer
+        .put_i64_ordered(offset + OBSERVATION_COUNT_OFFSET, entry.observation_count)
+        .unwrap();
+
+    let length = CHANNEL_OFFSET as usize + 8 + entry.channel.len() + entry.source.len();
+    offset + align(length, ENTRY_ALIGNMENT as usize) as IndexT
+}
+
+fn entry(stream_id: i32, channel: &str, source: &str) -> LossEntry {
+    LossEntry {
+        observation_count: 2,
+        total_bytes_lost: 4096,
+        first_observation_timestamp: 100,
+        last_observation_timestamp: 200,
+        session_id: 7,
+        stream_id,
+        channel: channel.to_string(),
+        source: source.to_string(),
+    }
+}
+
+#[test]
+fn should_read_no_entries_when_empty() {
+    let buffer = vec![0u8; CAPACITY];
+    assert_eq!(loss_report::read(&buffer, |_| panic!("No entries")), Ok(0));
+}
+
+#[test]
+fn should_read_entries_in_order() {
+    let mut buffer = vec![0u8; CAPACITY];
+    let first = entry(10, "aeron:udp?endpoint=localhost:40123", "127.0.0.1:54321");
+    let second = entry(11, "aeron:udp?endpoint=224.0.1.1:40456", "192.168.0.1:5000");
+    let offset = write_entry(&mut buffer, 0, &first);
+    write_entry(&mut buffer, offset, &second);
+
+    let mut entries = Vec::new();
+    let read = loss_report::read(&buffer, |e| entries.push(e.clone()));
+
+    assert_eq!(read, Ok(2));
+    assert_eq!(entries, vec![first, second]);
+}
+
+#[test]
+fn should_align_source_after_channel() {
+    let mut buffer = vec![0u8; CAPACITY];
+    // Channel length not a multiple of 4, so the source length is padded
+    let expected = entry(10, "aeron:ipc?a", "src");
+    write_entry(&mut buffer, 0, &expected);
+
+    let mut entries = Vec::new();
+    loss_report::read(&buffer, |e| entries.push(e.clone())).unwrap();
+    assert_eq!(entries, vec![expected]);
+}
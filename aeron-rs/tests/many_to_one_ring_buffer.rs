@@ -377,3 +377,170 @@ fn should_limit_read_of_messages() {
         Ok(record_length)
     );
 }
+
+#[test]
+fn should_not_unblock_when_empty() {
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+
+    assert_eq!(buffer.unblock(), Ok(false));
+}
+
+#[test]
+fn should_unblock_message_with_header() {
+    let message_length: IndexT = record_descriptor::ALIGNMENT * 4;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    buffer
+        .put_i64(HEAD_COUNTER_INDEX, message_length as i64)
+        .unwrap();
+    buffer
+        .put_i64(TAIL_COUNTER_INDEX, (message_length * 2) as i64)
+        .unwrap();
+    buffer
+        .put_i32(
+            record_descriptor::length_offset(message_length),
+            -message_length,
+        )
+        .unwrap();
+
+    assert_eq!(buffer.unblock(), Ok(true));
+    assert_eq!(
+        buffer.get_i32(record_descriptor::type_offset(message_length)),
+        Ok(record_descriptor::PADDING_MSG_TYPE_ID)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(message_length)),
+        Ok(message_length)
+    );
+}
+
+#[test]
+fn should_unblock_gap_with_zeros() {
+    let message_length: IndexT = record_descriptor::ALIGNMENT * 4;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    buffer
+        .put_i64(HEAD_COUNTER_INDEX, message_length as i64)
+        .unwrap();
+    buffer
+        .put_i64(TAIL_COUNTER_INDEX, (message_length * 3) as i64)
+        .unwrap();
+    buffer
+        .put_i32(
+            record_descriptor::length_offset(message_length * 2),
+            message_length,
+        )
+        .unwrap();
+
+    assert_eq!(buffer.unblock(), Ok(true));
+    assert_eq!(
+        buffer.get_i32(record_descriptor::type_offset(message_length)),
+        Ok(record_descriptor::PADDING_MSG_TYPE_ID)
+    );
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(message_length)),
+        Ok(message_length)
+    );
+}
+
+#[test]
+fn should_not_unblock_gap_with_message_race_on_second_message_increasing_tail() {
+    let message_length: IndexT = record_descriptor::ALIGNMENT * 4;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    buffer
+        .put_i64(HEAD_COUNTER_INDEX, message_length as i64)
+        .unwrap();
+    buffer
+        .put_i64(TAIL_COUNTER_INDEX, (message_length * 3) as i64)
+        .unwrap();
+
+    assert_eq!(buffer.unblock(), Ok(false));
+    assert_eq!(
+        buffer.get_i32(record_descriptor::length_offset(message_length)),
+        Ok(0)
+    );
+}
+
+#[test]
+fn should_resume_reading_after_unblock() {
+    let length: IndexT = 8;
+    let record_length: IndexT = length + record_descriptor::HEADER_LENGTH;
+    let aligned_record_length: IndexT = align(
+        record_length as usize,
+        record_descriptor::ALIGNMENT as usize,
+    ) as IndexT;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    // Simulate a producer that claimed space and wrote the header, but died
+    // before publishing the record length
+    buffer
+        .put_i64(TAIL_COUNTER_INDEX, aligned_record_length as i64)
+        .unwrap();
+    buffer
+        .put_i32(record_descriptor::type_offset(0), MSG_TYPE_ID)
+        .unwrap();
+    buffer
+        .put_i32(record_descriptor::length_offset(0), -record_length)
+        .unwrap();
+
+    let src_bytes = vec![0u8; BUFFER_SZ];
+    assert_eq!(buffer.write(MSG_TYPE_ID, &src_bytes, 0, length), Ok(true));
+
+    let mut times_called = 0;
+    assert_eq!(buffer.read(|_, _: &[u8]| times_called += 1), Ok(0));
+    assert_eq!(times_called, 0);
+
+    assert_eq!(buffer.unblock(), Ok(true));
+    assert_eq!(buffer.read(|_, _: &[u8]| times_called += 1), Ok(1));
+    assert_eq!(times_called, 1);
+    assert_eq!(
+        buffer.get_i64(HEAD_COUNTER_INDEX),
+        Ok((aligned_record_length * 2) as i64)
+    );
+}
+
+#[test]
+fn should_report_size_and_positions() {
+    let head: IndexT = 64;
+    let tail: IndexT = 256;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    assert_eq!(buffer.size(), 0);
+
+    buffer.put_i64(HEAD_COUNTER_INDEX, head as i64).unwrap();
+    buffer.put_i64(TAIL_COUNTER_INDEX, tail as i64).unwrap();
+
+    assert_eq!(buffer.consumer_position(), head as i64);
+    assert_eq!(buffer.producer_position(), tail as i64);
+    assert_eq!(buffer.size(), tail - head);
+}
+
+#[test]
+fn should_track_size_across_write_and_read() {
+    let length: IndexT = 8;
+    let record_length: IndexT = length + record_descriptor::HEADER_LENGTH;
+    let aligned_record_length: IndexT = align(
+        record_length as usize,
+        record_descriptor::ALIGNMENT as usize,
+    ) as IndexT;
+
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    let src_bytes = vec![0u8; BUFFER_SZ];
+
+    assert_eq!(buffer.write(MSG_TYPE_ID, &src_bytes, 0, length), Ok(true));
+    assert_eq!(buffer.size(), aligned_record_length);
+
+    assert_eq!(buffer.read(|_, _: &[u8]| {}), Ok(1));
+    assert_eq!(buffer.size(), 0);
+    assert_eq!(buffer.consumer_position(), aligned_record_length as i64);
+}
+
+#[test]
+fn should_write_consumer_heartbeat_time() {
+    let mut buffer = ManyToOneRingBuffer::new(vec![0u8; BUFFER_SZ]).unwrap();
+    assert_eq!(buffer.consumer_heartbeat_time(), 0);
+
+    buffer.set_consumer_heartbeat_time(1_234_567);
+    assert_eq!(buffer.consumer_heartbeat_time(), 1_234_567);
+}